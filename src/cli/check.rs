@@ -0,0 +1,285 @@
+use std::path::PathBuf;
+
+use pest::error::LineColLocation;
+
+use crate::cli::Error;
+use crate::compiler::{
+    AstError, Error as CompilerError, FileCompilerSource, HexoCompiler, HexoCompilerContext, Span,
+    Warning,
+};
+
+/// Outcome of compiling every source file matched by `check` patterns, nothing is written
+pub(crate) struct CheckReport {
+    results: Vec<CheckedFile>,
+}
+
+struct CheckedFile {
+    path: PathBuf,
+    result: Result<(), CompilerError>,
+    /// Reported by failed compilations too
+    warnings: Vec<Warning>,
+}
+
+impl CheckReport {
+    /// Compiles files matched by [patterns] in order, each file is compiled only once
+    pub(crate) fn run(
+        patterns: &[String],
+        context: impl Fn() -> HexoCompilerContext,
+    ) -> Result<CheckReport, Error> {
+        let mut paths: Vec<PathBuf> = Vec::new();
+        for pattern in patterns {
+            for path in expand_pattern(pattern)? {
+                if !paths.contains(&path) {
+                    paths.push(path);
+                }
+            }
+        }
+
+        let results = paths
+            .into_iter()
+            .map(|path| {
+                let compiler = HexoCompiler::new(context());
+                let result = compiler
+                    .compile(&FileCompilerSource::new(&path))
+                    .map(|_| ());
+                CheckedFile {
+                    path,
+                    result,
+                    warnings: compiler.warnings(),
+                }
+            })
+            .collect();
+
+        Ok(CheckReport { results })
+    }
+
+    pub(crate) fn checked(&self) -> usize {
+        self.results.len()
+    }
+
+    /// Files that failed to compile along with their errors
+    pub(crate) fn failures(&self) -> impl Iterator<Item = (&PathBuf, &CompilerError)> {
+        self.results.iter().filter_map(|checked| {
+            let error = checked.result.as_ref().err()?;
+            Some((&checked.path, error))
+        })
+    }
+
+    /// Renders errors and warnings of all files as a JSON document
+    pub(crate) fn to_json(&self) -> String {
+        let mut diagnostics = Vec::new();
+        for checked in &self.results {
+            let source = checked.path.display().to_string();
+            if let Err(error) = &checked.result {
+                diagnostics.extend(
+                    flatten(error)
+                        .into_iter()
+                        .map(|error| error_diagnostic_json(&source, error)),
+                );
+            }
+            diagnostics.extend(
+                checked
+                    .warnings
+                    .iter()
+                    .map(|warning| warning_diagnostic_json(&source, warning)),
+            );
+        }
+
+        report_json(self.checked(), self.failures().count(), &diagnostics)
+    }
+
+    /// Renders [error] that prevented checking as a JSON document with a single diagnostic
+    pub(crate) fn error_json(error: &Error) -> String {
+        let diagnostic = format!(
+            "{{\"source\":null,\"file\":null,\"line\":null,\"column\":null,\
+            \"severity\":\"error\",\"message\":{}}}",
+            json_string(&error.to_string())
+        );
+
+        report_json(0, 0, &[diagnostic])
+    }
+}
+
+/// Paths matched by [pattern], patterns without wildcards are taken as paths as they are
+fn expand_pattern(pattern: &str) -> Result<Vec<PathBuf>, Error> {
+    if !pattern.contains(['*', '?', '[']) {
+        return Ok(vec![PathBuf::from(pattern)]);
+    }
+
+    let paths: Vec<PathBuf> = glob::glob(pattern)
+        .map_err(|error| Error::InvalidSourcePattern {
+            pattern: pattern.to_string(),
+            reason: error.to_string(),
+        })?
+        .filter_map(Result::ok)
+        .filter(|path| path.is_file())
+        .collect();
+
+    if paths.is_empty() {
+        return Err(Error::InvalidSourcePattern {
+            pattern: pattern.to_string(),
+            reason: "no files match it".to_string(),
+        });
+    }
+
+    Ok(paths)
+}
+
+/// Individual errors of [error], [CompilerError::Multiple] is replaced by its content
+fn flatten(error: &CompilerError) -> Vec<&CompilerError> {
+    match error {
+        CompilerError::Multiple(errors) => errors.iter().flat_map(flatten).collect(),
+        _ => vec![error],
+    }
+}
+
+/// File, line and column the error points at along with the message without a source snippet
+fn describe(error: &CompilerError) -> (Option<(String, usize, usize)>, String) {
+    match error {
+        CompilerError::Io(e) => (None, e.to_string()),
+        CompilerError::Ast(AstError::Pest(e)) => {
+            let (line, column) = match e.line_col {
+                LineColLocation::Pos(position) => position,
+                LineColLocation::Span(start, _) => start,
+            };
+            let path = e.path().unwrap_or_default().to_string();
+            (Some((path, line, column)), e.variant.message().to_string())
+        }
+        CompilerError::Ast(e) => (None, e.to_string()),
+        CompilerError::Cst(e) => (Some(locate(e.span())), e.to_string()),
+        CompilerError::Rst(e) => (e.span().map(locate), e.to_string()),
+        CompilerError::Multiple(_) => (None, error.to_string()),
+    }
+}
+
+fn locate(span: &Span) -> (String, usize, usize) {
+    (
+        span.path().display().to_string(),
+        span.line(),
+        span.column(),
+    )
+}
+
+fn error_diagnostic_json(source: &str, error: &CompilerError) -> String {
+    let (location, message) = describe(error);
+    diagnostic_json(source, location, "error", &message)
+}
+
+fn warning_diagnostic_json(source: &str, warning: &Warning) -> String {
+    let location = locate(warning.span());
+    diagnostic_json(source, Some(location), "warning", &warning.to_string())
+}
+
+fn diagnostic_json(
+    source: &str,
+    location: Option<(String, usize, usize)>,
+    severity: &str,
+    message: &str,
+) -> String {
+    let (file, line, column) = match location {
+        Some((file, line, column)) => (json_string(&file), line.to_string(), column.to_string()),
+        // errors without location, like unreadable file, are reported against the source itself
+        None => (json_string(source), "null".to_string(), "null".to_string()),
+    };
+
+    format!(
+        "{{\"source\":{},\"file\":{},\"line\":{},\"column\":{},\"severity\":{},\"message\":{}}}",
+        json_string(source),
+        file,
+        line,
+        column,
+        json_string(severity),
+        json_string(message)
+    )
+}
+
+fn report_json(checked: usize, failed: usize, diagnostics: &[String]) -> String {
+    format!(
+        "{{\"checked\":{},\"failed\":{},\"diagnostics\":[{}]}}",
+        checked,
+        failed,
+        diagnostics.join(",")
+    )
+}
+
+fn json_string(value: &str) -> String {
+    let mut result = String::with_capacity(value.len() + 2);
+    result.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            '\t' => result.push_str("\\t"),
+            c if (c as u32) < 0x20 => result.push_str(&format!("\\u{:04x}", c as u32)),
+            c => result.push(c),
+        }
+    }
+    result.push('"');
+
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn json_diagnostics() {
+        let patterns = vec![
+            "samples/len/input.hexo".to_string(),
+            "samples/include_cycle/*.hexo".to_string(),
+            "samples/len/input.hexo".to_string(),
+        ];
+        let report = CheckReport::run(&patterns, || HexoCompilerContext::new(false)).unwrap();
+
+        assert_eq!(report.checked(), 1 + 2);
+        assert_eq!(report.failures().count(), 2);
+
+        let json = report.to_json();
+        assert!(
+            json.starts_with("{\"checked\":3,\"failed\":2,\"diagnostics\":[{"),
+            "{json}"
+        );
+        assert!(
+            json.contains("\"severity\":\"error\",\"message\":\"Include cycle: "),
+            "{json}"
+        );
+    }
+
+    #[test]
+    fn json_warnings() {
+        let patterns = vec!["samples/len/input.hexo".to_string()];
+        let context =
+            || HexoCompilerContext::new(false).with_definitions(vec!["verison=02".to_string()]);
+        let report = CheckReport::run(&patterns, context).unwrap();
+
+        assert_eq!(report.failures().count(), 0);
+
+        let json = report.to_json();
+        assert!(
+            json.contains(
+                "\"file\":\"--define\",\"line\":1,\"column\":1,\"severity\":\"warning\",\
+                \"message\":\"Definition verison doesn't match any constant declared with `$?`\""
+            ),
+            "{json}"
+        );
+    }
+
+    #[test]
+    fn json_string_escapes() {
+        assert_eq!(
+            json_string("a\"b\\c\nd\u{1}é"),
+            "\"a\\\"b\\\\c\\nd\\u0001é\""
+        );
+    }
+
+    #[test]
+    fn unmatched_pattern() {
+        let patterns = vec!["samples/*/missing_*.hexo".to_string()];
+        let result = CheckReport::run(&patterns, || HexoCompilerContext::new(false));
+
+        assert!(matches!(result, Err(Error::InvalidSourcePattern { .. })));
+    }
+}
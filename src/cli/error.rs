@@ -6,6 +6,20 @@ pub(crate) enum Error {
     FileWatcher(notify::Error),
     CantCrateOutputFile(std::io::Error),
     Compilation(crate::compiler::Error),
+    InvalidSourcePattern {
+        pattern: String,
+        reason: String,
+    },
+}
+
+impl Error {
+    /// Number of individual errors, compilation may report several of them at once
+    pub(crate) fn count(&self) -> usize {
+        match self {
+            Error::Compilation(e) => e.count(),
+            _ => 1,
+        }
+    }
 }
 
 impl Display for Error {
@@ -15,6 +29,9 @@ impl Display for Error {
             Error::FileWatcher(e) => write!(f, "File watching error:\n{}", e),
             Error::CantCrateOutputFile(e) => write!(f, "Can't create output file:\n{}", e),
             Error::Compilation(e) => write!(f, "Compilation error:\n{}", e),
+            Error::InvalidSourcePattern { pattern, reason } => {
+                write!(f, "Invalid source pattern {}: {}", pattern, reason)
+            }
         }
     }
 }
@@ -1,6 +1,8 @@
+use std::fmt::{Display, Formatter};
 use std::fs::File;
 use std::io::Write;
 use std::panic::catch_unwind;
+use std::process::exit;
 use std::path::Path;
 use std::thread::sleep;
 use std::time::{Duration, Instant};
@@ -14,9 +16,11 @@ use notify::{Event, RecursiveMode, Watcher};
 
 pub(crate) use error::Error;
 
-use crate::compiler::{FileCompilerSource, HexoCompiler, HexoCompilerContext};
+use crate::compiler::{FileCompilerSource, HexoCompiler, HexoCompilerContext, NativeFunctionIndex};
 
+mod check;
 mod error;
+use crate::cli::check::CheckReport;
 use crate::util::logger::LogLevel;
 use crate::util::{defer, logger};
 
@@ -29,6 +33,9 @@ enum Commands {
 
         #[arg(short, long)]
         output: Option<String>,
+
+        #[arg(short = 'D', long = "define", value_name = "NAME=VALUE")]
+        definitions: Vec<String>,
     },
 
     #[command(about = "Watch source and write compiled output on change")]
@@ -38,12 +45,38 @@ enum Commands {
 
         #[arg(short, long)]
         output: Option<String>,
+
+        #[arg(short = 'D', long = "define", value_name = "NAME=VALUE")]
+        definitions: Vec<String>,
+    },
+
+    #[command(about = "Check that sources compile without writing any output")]
+    Check {
+        /// Source files or glob patterns, like `samples/**/*.hexo`
+        #[arg(required = true)]
+        sources: Vec<String>,
+
+        #[arg(short = 'D', long = "define", value_name = "NAME=VALUE")]
+        definitions: Vec<String>,
+
+        #[arg(short, long, default_value_t = CheckFormat::Text)]
+        format: CheckFormat,
     },
+
+    #[command(about = "List available native functions")]
+    Functions,
+}
+
+/// Output of `check` command, [CheckFormat::Json] prints nothing but the JSON report
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum CheckFormat {
+    Text,
+    Json,
 }
 
 #[derive(Parser)]
 #[command(version, about, long_about = None)]
-pub(crate) struct Cli {
+pub struct Cli {
     #[command(subcommand)]
     command: Option<Commands>,
 
@@ -52,21 +85,43 @@ pub(crate) struct Cli {
 
     #[arg(short, long, default_value_t = false)]
     safe: bool,
+
+    #[arg(long, default_value_t = HexoCompilerContext::DEFAULT_MAX_CALL_DEPTH)]
+    max_call_depth: usize,
+
+    #[arg(long, default_value_t = HexoCompilerContext::DEFAULT_MAX_LOOP_ITERATIONS)]
+    max_loop_iterations: usize,
+
+    #[arg(long, default_value_t = HexoCompilerContext::DEFAULT_MAX_OUTPUT_SIZE)]
+    max_output_size: usize,
 }
 
 #[derive(Copy, Clone, Debug)]
 pub(crate) struct CliCompilerArguments {
     safe_mode: bool,
+    max_call_depth: usize,
+    max_loop_iterations: usize,
+    max_output_size: usize,
 }
 
 impl CliCompilerArguments {
-    pub(crate) fn new(safe_mode: bool) -> CliCompilerArguments {
-        CliCompilerArguments { safe_mode }
+    pub(crate) fn new(
+        safe_mode: bool,
+        max_call_depth: usize,
+        max_loop_iterations: usize,
+        max_output_size: usize,
+    ) -> CliCompilerArguments {
+        CliCompilerArguments {
+            safe_mode,
+            max_call_depth,
+            max_loop_iterations,
+            max_output_size,
+        }
     }
 }
 
 impl Cli {
-    pub(crate) fn run() {
+    pub fn run() {
         let build_started = Instant::now();
 
         let cli = Cli::parse();
@@ -78,11 +133,35 @@ impl Cli {
         let compiler_arguments = cli.cli_compiler_arguments();
         let cli_result: Result<_, Error> = match cli.command {
             None => Err(Error::UnknownCommand),
-            Some(Commands::Watch { source, output }) => {
-                Self::watch(source, output, compiler_arguments)
+            Some(Commands::Watch {
+                source,
+                output,
+                definitions,
+            }) => Self::watch(source, output, definitions, compiler_arguments),
+            Some(Commands::Build {
+                source,
+                output,
+                definitions,
+            }) => Self::build(source, output, definitions, compiler_arguments),
+            Some(Commands::Check {
+                sources,
+                definitions,
+                format: CheckFormat::Text,
+            }) => {
+                let passed = Self::check(sources, definitions, compiler_arguments);
+                exit(if passed { 0 } else { 1 });
+            }
+            Some(Commands::Check {
+                sources,
+                definitions,
+                format: CheckFormat::Json,
+            }) => {
+                let passed = Self::check_json(sources, definitions, compiler_arguments);
+                exit(if passed { 0 } else { 1 });
             }
-            Some(Commands::Build { source, output }) => {
-                Self::build(source, output, compiler_arguments)
+            Some(Commands::Functions) => {
+                Self::list_functions();
+                return;
             }
         };
 
@@ -90,22 +169,41 @@ impl Cli {
     }
 
     fn cli_compiler_arguments(&self) -> CliCompilerArguments {
-        CliCompilerArguments::new(self.safe)
+        CliCompilerArguments::new(
+            self.safe,
+            self.max_call_depth,
+            self.max_loop_iterations,
+            self.max_output_size,
+        )
     }
 
     fn log_debug_interface_arguments(&self) {
         logger::debug!(
             "initialized cli interface with arguments:\
             \n  --log-level = {}\
-            \n  --safe = {}",
+            \n  --safe = {}\
+            \n  --max-call-depth = {}\
+            \n  --max-loop-iterations = {}\
+            \n  --max-output-size = {}",
             &self.log_level,
-            &self.safe
+            &self.safe,
+            &self.max_call_depth,
+            &self.max_loop_iterations,
+            &self.max_output_size
         );
     }
 
     fn handle_cli_error_if_required(cli_result: Result<(), Error>, build_started: Instant) {
         if let Err(e) = cli_result {
+            let error_count = e.count();
             Self::print_error(e.into());
+
+            logger::output!(
+                "{} {}",
+                style("hexo compilation failed, errors:").red(),
+                error_count
+            );
+            exit(1);
         } else {
             let build_duration = Instant::now() - build_started;
 
@@ -117,6 +215,23 @@ impl Cli {
         }
     }
 
+    fn list_functions() {
+        let index = NativeFunctionIndex::default();
+        let mut functions: Vec<_> = index.functions().iter().collect();
+        functions.sort_by_key(|function| function.signature().name());
+
+        for function in functions {
+            let signature = function.signature();
+            let safety = if signature.is_safe() { "" } else { " [unsafe]" };
+            logger::output!(
+                "{}{}\n    {}",
+                style(signature).bold(),
+                style(safety).red(),
+                signature.doc()
+            );
+        }
+    }
+
     fn print_error(error: Box<dyn std::error::Error>) {
         logger::error!("{error}");
     }
@@ -124,13 +239,20 @@ impl Cli {
     fn watch(
         source: String,
         output: Option<String>,
+        definitions: Vec<String>,
         compiler_arguments: CliCompilerArguments,
     ) -> Result<(), Error> {
         let source_path_clone = source.clone();
         let source_path = source_path_clone.as_ref();
 
         let mut watcher = notify::recommended_watcher(move |event: Result<Event, _>| {
-            Self::watch_loop(source.clone(), output.clone(), compiler_arguments, event)
+            Self::watch_loop(
+                source.clone(),
+                output.clone(),
+                definitions.clone(),
+                compiler_arguments,
+                event,
+            )
         })
         .map_err(Error::FileWatcher)?;
 
@@ -148,6 +270,7 @@ impl Cli {
     fn watch_loop(
         source: String,
         output: Option<String>,
+        definitions: Vec<String>,
         compiler_arguments: CliCompilerArguments,
         event: Result<Event, notify::Error>,
     ) {
@@ -156,7 +279,12 @@ impl Cli {
                 if let Modify(ModifyKind::Data(_)) = e.kind {
                     logger::debug!("rebuilding...");
                     let _ = catch_unwind(|| {
-                        Self::build(source.clone(), output.clone(), compiler_arguments)
+                        Self::build(
+                            source.clone(),
+                            output.clone(),
+                            definitions.clone(),
+                            compiler_arguments,
+                        )
                     });
                     logger::debug!(" done!");
                 }
@@ -170,12 +298,18 @@ impl Cli {
     pub(crate) fn build(
         source: String,
         output: Option<String>,
+        definitions: Vec<String>,
         compiler_arguments: CliCompilerArguments,
     ) -> Result<(), Error> {
         defer!(logger::debug!("BUILDING, done"));
-        logger::debug!("BUILDING, source: {}, output: {:?}", source, output);
+        logger::debug!(
+            "BUILDING, source: {}, output: {:?}, definitions: {:?}",
+            source,
+            output,
+            definitions
+        );
 
-        let context = HexoCompilerContext::new(compiler_arguments.safe_mode);
+        let context = Self::compiler_context(compiler_arguments, definitions);
         let compiler = HexoCompiler::new(context);
 
         let source_path = Path::new(&source);
@@ -192,6 +326,99 @@ impl Cli {
             .write_all(compilation_result.content.iter().as_slice())
             .map_err(Error::CantCrateOutputFile)
     }
+
+    fn check(
+        sources: Vec<String>,
+        definitions: Vec<String>,
+        compiler_arguments: CliCompilerArguments,
+    ) -> bool {
+        let report = CheckReport::run(&sources, || {
+            Self::compiler_context(compiler_arguments, definitions.clone())
+        });
+
+        let report = match report {
+            Ok(report) => report,
+            Err(error) => {
+                Self::print_error(error.into());
+                return false;
+            }
+        };
+
+        for (path, error) in report.failures() {
+            logger::error!("{} doesn't compile:\n{}", path.display(), error);
+        }
+
+        // check writes no output, so the summary is about files instead of the compilation
+        let failed = report.failures().count();
+        let summary = format!("hexo checked files: {}, failed:", report.checked());
+        if failed > 0 {
+            logger::output!("{} {}", style(summary).red(), failed);
+        } else {
+            logger::output!("{} {}", style(summary).green(), failed);
+        }
+        failed == 0
+    }
+
+    fn check_json(
+        sources: Vec<String>,
+        definitions: Vec<String>,
+        compiler_arguments: CliCompilerArguments,
+    ) -> bool {
+        // anything logged would break the JSON printed to the same output
+        logger::HexoLogger::set_level(&LogLevel::None);
+
+        let report = CheckReport::run(&sources, || {
+            Self::compiler_context(compiler_arguments, definitions.clone())
+        });
+
+        match report {
+            Ok(report) => {
+                logger::output!("{}", report.to_json());
+                report.failures().next().is_none()
+            }
+            Err(error) => {
+                logger::output!("{}", CheckReport::error_json(&error));
+                false
+            }
+        }
+    }
+
+    fn compiler_context(
+        compiler_arguments: CliCompilerArguments,
+        definitions: Vec<String>,
+    ) -> HexoCompilerContext {
+        HexoCompilerContext::new(compiler_arguments.safe_mode)
+            .with_max_call_depth(compiler_arguments.max_call_depth)
+            .with_max_loop_iterations(compiler_arguments.max_loop_iterations)
+            .with_max_output_size(compiler_arguments.max_output_size)
+            .with_definitions(definitions)
+    }
+}
+
+impl ValueEnum for CheckFormat {
+    fn value_variants<'a>() -> &'a [Self] {
+        &[Self::Text, Self::Json]
+    }
+
+    fn to_possible_value(&self) -> Option<PossibleValue> {
+        let name = match self {
+            CheckFormat::Text => "text",
+            CheckFormat::Json => "json",
+        };
+
+        Some(PossibleValue::new(name))
+    }
+}
+
+impl Display for CheckFormat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            CheckFormat::Text => "text",
+            CheckFormat::Json => "json",
+        };
+
+        write!(f, "{}", name)
+    }
 }
 
 impl ValueEnum for LogLevel {
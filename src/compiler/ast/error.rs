@@ -2,7 +2,7 @@ use crate::compiler::ast::parser::Rule;
 use std::fmt::Display;
 
 #[derive(Debug)]
-pub(crate) enum Error {
+pub enum Error {
     Pest(Box<pest::error::Error<Rule>>),
     UnknownRule { rule_name: String },
 }
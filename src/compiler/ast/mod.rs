@@ -2,6 +2,6 @@ mod error;
 mod node;
 mod parser;
 
-pub(crate) use error::Error;
+pub use error::Error;
 pub(crate) use node::{AstNode, AstNodeType};
 pub(crate) use parser::AstParser;
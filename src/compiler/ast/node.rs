@@ -1,8 +1,11 @@
+use crate::compiler::span::Span;
+
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
-pub(crate) enum AstNodeType {
+pub enum AstNodeType {
     File,
 
     StatementConst,
+    StatementConstOverridable,
     StatementConstName,
 
     StatementEmit,
@@ -10,10 +13,41 @@ pub(crate) enum AstNodeType {
     StatementFn,
     StatementFnName,
     StatementFnBody,
+    StatementFnParams,
+    StatementFnParam,
+    StatementFnParamName,
+    StatementFnParamDefault,
+
+    StatementInclude,
+
+    StatementLabel,
+    StatementLabelName,
+
+    StatementIf,
+    IfCondition,
+    IfConditionNot,
+    IfConditionDefined,
+    IfConditionCompare,
+    IfConditionOperator,
+    IfConditionValue,
+    IfBody,
+
+    StatementFor,
+    ForVariable,
+    ForRange,
+    ForRangeValue,
+    ForValues,
+    ForBody,
+
+    StatementAssert,
+    AssertMessage,
 
     AtomUtf8,
+    AtomUtf8Raw,
     AtomHex,
     AtomConst,
+    AtomLabel,
+    AtomLabelName,
     AtomFn,
     AtomFnParam,
     AtomFnParamValue,
@@ -22,8 +56,14 @@ pub(crate) enum AstNodeType {
     AtomBaseNumber,
     AtomBaseNumberBase,
     AtomBaseNumberValue,
+    AtomIntFormat,
+    AtomExpr,
 
     AtomFnName,
+
+    Expr,
+    ExprLabel,
+    ExprOperator,
 }
 
 impl AstNodeType {
@@ -31,14 +71,22 @@ impl AstNodeType {
         matches!(
             self,
             AstNodeType::AtomUtf8
+                | AstNodeType::AtomUtf8Raw
                 | AstNodeType::AtomHex
                 | AstNodeType::AtomFnName
                 | AstNodeType::StatementConstName
                 | AstNodeType::AtomBaseNumberBase
                 | AstNodeType::AtomBaseNumberValue
                 | AstNodeType::StatementFnName
+                | AstNodeType::StatementFnParamName
                 | AstNodeType::AtomFnParamIdentifier
                 | AstNodeType::AtomConst
+                | AstNodeType::StatementLabelName
+                | AstNodeType::AtomLabelName
+                | AstNodeType::AtomIntFormat
+                | AstNodeType::ExprOperator
+                | AstNodeType::IfConditionOperator
+                | AstNodeType::ForVariable
         )
     }
 }
@@ -48,6 +96,7 @@ pub(crate) struct AstNode {
     node_type: AstNodeType,
     content: Option<String>,
     children: Vec<AstNode>,
+    span: Span,
 }
 
 impl AstNode {
@@ -55,11 +104,13 @@ impl AstNode {
         node_type: AstNodeType,
         content: Option<String>,
         children: Vec<AstNode>,
+        span: Span,
     ) -> Self {
         AstNode {
             node_type,
             content,
             children,
+            span,
         }
     }
 
@@ -74,4 +125,8 @@ impl AstNode {
     pub(crate) fn content(&self) -> Option<&String> {
         self.content.as_ref()
     }
+
+    pub(crate) fn span(&self) -> &Span {
+        &self.span
+    }
 }
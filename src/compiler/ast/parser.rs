@@ -1,9 +1,13 @@
+use std::path::Path;
+use std::sync::Arc;
+
 use pest::iterators::Pair;
 use pest::Parser;
 use pest_derive::Parser;
 
 use crate::compiler::ast::Error;
 use crate::compiler::ast::{AstNode, AstNodeType};
+use crate::compiler::span::{Span, SpanSource};
 
 #[derive(Parser)]
 #[grammar = "grammar.pest"]
@@ -13,16 +17,32 @@ struct AstPestParser;
 pub(crate) struct AstParser {}
 
 impl AstParser {
-    pub(crate) fn parse(&self, source: &str) -> Result<AstNode, Error> {
-        let pairs =
-            AstPestParser::parse(Rule::file, source).map_err(|e| Error::Pest(Box::new(e)))?;
+    pub(crate) fn parse(&self, path: &Path, source: &str) -> Result<AstNode, Error> {
+        Self::parse_rule(Rule::file, path, source)
+    }
+
+    /// Parses `name=value` [source] into file node holding single constant statement
+    pub(crate) fn parse_definition(&self, path: &Path, source: &str) -> Result<AstNode, Error> {
+        Self::parse_rule(Rule::definition, path, source)
+    }
 
+    fn parse_rule(rule: Rule, path: &Path, source: &str) -> Result<AstNode, Error> {
+        let pairs = AstPestParser::parse(rule, source).map_err(|e| {
+            Error::Pest(Box::new(e.with_path(path.to_string_lossy().as_ref())))
+        })?;
+
+        let span_source = Arc::new(SpanSource::new(path, source));
         let children: Result<Vec<AstNode>, _> = pairs
-            .map(parse_ast_pair)
+            .map(|p| parse_ast_pair(&span_source, p))
             .filter_map(filter_ignored_token)
             .collect();
 
-        Ok(AstNode::new(AstNodeType::File, None, children?))
+        Ok(AstNode::new(
+            AstNodeType::File,
+            None,
+            children?,
+            Span::new(span_source, 0, source.len()),
+        ))
     }
 }
 
@@ -34,14 +54,30 @@ fn filter_ignored_token(result: Result<Option<AstNode>, Error>) -> Option<Result
     }
 }
 
-fn parse_ast_pair(p: Pair<Rule>) -> Result<Option<AstNode>, Error> {
+fn parse_ast_pair(span_source: &Arc<SpanSource>, p: Pair<Rule>) -> Result<Option<AstNode>, Error> {
     let node_type = match p.as_rule() {
         Rule::atom_utf8 => AstNodeType::AtomUtf8,
+        Rule::atom_utf8_raw => AstNodeType::AtomUtf8Raw,
+        // quotes are only matched atomically to keep whitespace at string ends
+        Rule::atom_utf8_decor | Rule::atom_utf8_raw_decor => {
+            return match p.into_inner().next() {
+                Some(inner) => parse_ast_pair(span_source, inner),
+                None => Ok(None),
+            };
+        }
         Rule::atom_hex => AstNodeType::AtomHex,
         Rule::atom_base_number => AstNodeType::AtomBaseNumber,
         Rule::atom_base_number_base => AstNodeType::AtomBaseNumberBase,
         Rule::atom_base_number_value => AstNodeType::AtomBaseNumberValue,
         Rule::atom_const => AstNodeType::AtomConst,
+        Rule::atom_label => AstNodeType::AtomLabel,
+        Rule::atom_label_name => AstNodeType::AtomLabelName,
+        Rule::atom_int_format => AstNodeType::AtomIntFormat,
+        Rule::atom_expr => AstNodeType::AtomExpr,
+
+        Rule::expr => AstNodeType::Expr,
+        Rule::expr_label => AstNodeType::ExprLabel,
+        Rule::expr_operator => AstNodeType::ExprOperator,
 
         Rule::atom_fn => AstNodeType::AtomFn,
         Rule::atom_fn_name => AstNodeType::AtomFnName,
@@ -49,16 +85,47 @@ fn parse_ast_pair(p: Pair<Rule>) -> Result<Option<AstNode>, Error> {
         Rule::atom_fn_params => AstNodeType::AtomFnParams,
 
         Rule::const_statement => AstNodeType::StatementConst,
+        Rule::const_statement_overridable => AstNodeType::StatementConstOverridable,
         Rule::const_statement_name => AstNodeType::StatementConstName,
+        // definition has the same shape as constant statement, only written as `name=value`
+        Rule::definition_statement => AstNodeType::StatementConst,
 
         Rule::fn_statement => AstNodeType::StatementFn,
         Rule::fn_statement_name => AstNodeType::StatementFnName,
         Rule::fn_statement_body => AstNodeType::StatementFnBody,
+        Rule::fn_statement_params => AstNodeType::StatementFnParams,
+        Rule::fn_statement_param => AstNodeType::StatementFnParam,
+        Rule::fn_statement_param_name => AstNodeType::StatementFnParamName,
+        Rule::fn_statement_param_default => AstNodeType::StatementFnParamDefault,
         Rule::atom_fn_param_value => AstNodeType::AtomFnParamValue,
         Rule::atom_fn_param_identifier => AstNodeType::AtomFnParamIdentifier,
 
         Rule::emit_statement => AstNodeType::StatementEmit,
 
+        Rule::include_statement => AstNodeType::StatementInclude,
+
+        Rule::label_statement => AstNodeType::StatementLabel,
+        Rule::label_statement_name => AstNodeType::StatementLabelName,
+
+        Rule::if_statement => AstNodeType::StatementIf,
+        Rule::if_condition => AstNodeType::IfCondition,
+        Rule::if_condition_not => AstNodeType::IfConditionNot,
+        Rule::if_condition_defined => AstNodeType::IfConditionDefined,
+        Rule::if_condition_compare => AstNodeType::IfConditionCompare,
+        Rule::if_condition_operator => AstNodeType::IfConditionOperator,
+        Rule::if_condition_value => AstNodeType::IfConditionValue,
+        Rule::if_body => AstNodeType::IfBody,
+
+        Rule::for_statement => AstNodeType::StatementFor,
+        Rule::for_variable => AstNodeType::ForVariable,
+        Rule::for_range => AstNodeType::ForRange,
+        Rule::for_range_value => AstNodeType::ForRangeValue,
+        Rule::for_values => AstNodeType::ForValues,
+        Rule::for_body => AstNodeType::ForBody,
+
+        Rule::assert_statement => AstNodeType::StatementAssert,
+        Rule::assert_message => AstNodeType::AssertMessage,
+
         Rule::EOI => return Ok(None),
         _ => {
             return Err(Error::UnknownRule {
@@ -71,11 +138,14 @@ fn parse_ast_pair(p: Pair<Rule>) -> Result<Option<AstNode>, Error> {
         .must_capture_value()
         .then(|| p.as_str().to_string());
 
+    let pest_span = p.as_span();
+    let span = Span::new(span_source.clone(), pest_span.start(), pest_span.end());
+
     let children: Result<Vec<AstNode>, _> = p
         .into_inner()
-        .map(parse_ast_pair)
+        .map(|p| parse_ast_pair(span_source, p))
         .filter_map(filter_ignored_token)
         .collect();
 
-    Ok(Some(AstNode::new(node_type, node_value, children?)))
+    Ok(Some(AstNode::new(node_type, node_value, children?, span)))
 }
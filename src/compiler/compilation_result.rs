@@ -1,5 +1,6 @@
-pub(crate) struct Compilation {
-    pub(crate) content: Vec<u8>,
+/// Binary output of a successful compilation
+pub struct Compilation {
+    pub content: Vec<u8>,
 }
 
 impl Compilation {
@@ -1,13 +1,122 @@
-pub(crate) struct HexoCompilerContext {
+use std::collections::HashSet;
+
+use crate::compiler::native_fn::{
+    Error, NativeFunction, NativeFunctionDefinition, NativeFunctionIndex, NativeFunctionSignature,
+};
+
+pub struct HexoCompilerContext {
     safe_mode: bool,
+    max_call_depth: usize,
+    max_loop_iterations: usize,
+    max_output_size: usize,
+    /// `name=value` overrides of constants declared at the top level of compiled file
+    definitions: Vec<String>,
+    native_functions: NativeFunctionIndex,
+    /// Functions allowed in safe mode, [None] allows the ones with safe signature
+    safe_functions: Option<HashSet<String>>,
 }
 
 impl HexoCompilerContext {
-    pub(crate) fn new(safe_mode: bool) -> Self {
-        HexoCompilerContext { safe_mode }
+    /// Default limit of nested user function calls
+    pub const DEFAULT_MAX_CALL_DEPTH: usize = 256;
+    /// Default limit of iterations of a single loop
+    pub const DEFAULT_MAX_LOOP_ITERATIONS: usize = 65536;
+    /// Default limit of bytes produced by a single native function call, 256 MiB
+    pub const DEFAULT_MAX_OUTPUT_SIZE: usize = 1 << 28;
+
+    pub fn new(safe_mode: bool) -> Self {
+        HexoCompilerContext {
+            safe_mode,
+            max_call_depth: Self::DEFAULT_MAX_CALL_DEPTH,
+            max_loop_iterations: Self::DEFAULT_MAX_LOOP_ITERATIONS,
+            max_output_size: Self::DEFAULT_MAX_OUTPUT_SIZE,
+            definitions: Vec::new(),
+            native_functions: NativeFunctionIndex::default(),
+            safe_functions: None,
+        }
+    }
+
+    /// Sets limit of nested user function calls, deeper recursion is reported as an error
+    pub fn with_max_call_depth(self, max_call_depth: usize) -> Self {
+        HexoCompilerContext {
+            max_call_depth,
+            ..self
+        }
+    }
+
+    /// Sets limit of iterations of a single loop, longer loops are reported as an error
+    pub fn with_max_loop_iterations(self, max_loop_iterations: usize) -> Self {
+        HexoCompilerContext {
+            max_loop_iterations,
+            ..self
+        }
+    }
+
+    /// Sets limit of bytes produced by a single native function call, like `repeat` or `fill`
+    pub fn with_max_output_size(self, max_output_size: usize) -> Self {
+        HexoCompilerContext {
+            max_output_size,
+            ..self
+        }
+    }
+
+    /// Sets `name=value` [definitions] of constants, they are bound before constants of the file
+    pub fn with_definitions(self, definitions: Vec<String>) -> Self {
+        HexoCompilerContext {
+            definitions,
+            ..self
+        }
+    }
+
+    /// Allows only [names] in safe mode, regardless of safety declared by function signatures
+    pub fn with_safe_functions(self, names: &[&str]) -> Self {
+        HexoCompilerContext {
+            safe_functions: Some(names.iter().map(|name| name.to_string()).collect()),
+            ..self
+        }
+    }
+
+    /// Makes [function] callable from compiled sources, fails if its name is already taken
+    pub fn register_native_function(&mut self, function: NativeFunction) -> Result<(), Error> {
+        self.native_functions.register(function)
     }
 
-    pub(crate) fn safe_mode(&self) -> bool {
+    pub fn register_native_function_definition(
+        &mut self,
+        definition: &dyn NativeFunctionDefinition,
+    ) -> Result<(), Error> {
+        self.register_native_function(definition.create())
+    }
+
+    pub fn safe_mode(&self) -> bool {
         self.safe_mode
     }
+
+    pub fn max_call_depth(&self) -> usize {
+        self.max_call_depth
+    }
+
+    pub fn max_loop_iterations(&self) -> usize {
+        self.max_loop_iterations
+    }
+
+    pub fn max_output_size(&self) -> usize {
+        self.max_output_size
+    }
+
+    pub fn definitions(&self) -> &Vec<String> {
+        &self.definitions
+    }
+
+    pub(crate) fn native_functions(&self) -> &NativeFunctionIndex {
+        &self.native_functions
+    }
+
+    /// Tells whether function with [signature] may be called in safe mode
+    pub(crate) fn is_safe_function(&self, signature: &NativeFunctionSignature) -> bool {
+        match &self.safe_functions {
+            Some(names) => names.contains(signature.name()),
+            None => signature.is_safe(),
+        }
+    }
 }
@@ -4,18 +4,24 @@ use std::fs::File;
 use std::io::Read;
 use std::path::{Path, PathBuf};
 
-pub(crate) trait CompilerSource {
+pub trait CompilerSource {
     fn read(&self) -> Result<String, std::io::Error>;
 
     fn path(&self) -> &Path;
+
+    /// Source of the file at [path] included by this source or by any file it includes, [path] is
+    /// already resolved against the including file. Files are read from disk by default
+    fn include(&self, path: &Path) -> Box<dyn CompilerSource> {
+        Box::new(FileCompilerSource::new(path))
+    }
 }
 
-pub(crate) struct FileCompilerSource {
+pub struct FileCompilerSource {
     path: PathBuf,
 }
 
 impl FileCompilerSource {
-    pub(crate) fn new(path: &Path) -> FileCompilerSource {
+    pub fn new(path: &Path) -> FileCompilerSource {
         FileCompilerSource {
             path: path.to_path_buf(),
         }
@@ -37,7 +43,7 @@ impl CompilerSource for FileCompilerSource {
     }
 }
 
-pub(crate) struct LiteralCompilerSource {
+pub struct LiteralCompilerSource {
     content: String,
     path: PathBuf,
 }
@@ -53,7 +59,7 @@ impl CompilerSource for LiteralCompilerSource {
 }
 
 impl LiteralCompilerSource {
-    pub(crate) fn anonymous(content: String) -> LiteralCompilerSource {
+    pub fn anonymous(content: String) -> LiteralCompilerSource {
         LiteralCompilerSource {
             content: content,
             path: Path::new(format!("hexo://anonymous/{}", HexoId::next()).as_str()).to_path_buf(),
@@ -79,7 +85,7 @@ pub(crate) mod tests {
         }
 
         fn path(&self) -> &Path {
-            return self.path.as_path();
+            self.path.as_path()
         }
     }
 
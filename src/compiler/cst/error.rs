@@ -1,44 +1,73 @@
 use crate::compiler::ast::AstNodeType;
+use crate::compiler::span::Span;
 use std::fmt::{Display, Formatter};
 
 #[derive(Debug)]
-pub(crate) enum Error {
+pub enum Error {
     UnexpectedNode {
         expected: Vec<AstNodeType>,
         actual: AstNodeType,
+        span: Span,
     },
     MalformedNodeValue {
         message: String,
+        span: Span,
     },
     MissingContent {
         node_type: AstNodeType,
+        span: Span,
     },
     UnexpectedChildren {
         node_type: AstNodeType,
         children: Vec<AstNodeType>,
+        span: Span,
+    },
+    DuplicateNode {
+        span: Span,
+    },
+    InvalidEscape {
+        escape: String,
+        message: String,
+        span: Span,
     },
-    DuplicateNode,
+}
+
+impl Error {
+    /// Location of the source code that caused the error
+    pub fn span(&self) -> &Span {
+        match self {
+            Error::UnexpectedNode { span, .. } => span,
+            Error::MalformedNodeValue { span, .. } => span,
+            Error::MissingContent { span, .. } => span,
+            Error::UnexpectedChildren { span, .. } => span,
+            Error::DuplicateNode { span } => span,
+            Error::InvalidEscape { span, .. } => span,
+        }
+    }
 }
 
 impl Display for Error {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
-            Error::UnexpectedNode { expected, actual } => {
+            Error::UnexpectedNode {
+                expected, actual, ..
+            } => {
                 write!(
                     f,
                     "Unexpected node. Expected {:?}, got {:?}",
                     expected, actual
                 )
             }
-            Error::MalformedNodeValue { message } => {
+            Error::MalformedNodeValue { message, .. } => {
                 write!(f, "Malformed node value: {}", message)
             }
-            Error::MissingContent { node_type } => {
+            Error::MissingContent { node_type, .. } => {
                 write!(f, "Missing content for node type {:?}", node_type)
             }
             Error::UnexpectedChildren {
                 node_type,
                 children,
+                ..
             } => {
                 write!(
                     f,
@@ -46,9 +75,14 @@ impl Display for Error {
                     node_type, children
                 )
             }
-            Error::DuplicateNode => {
+            Error::DuplicateNode { .. } => {
                 write!(f, "Duplicate node")
             }
+            Error::InvalidEscape {
+                escape, message, ..
+            } => {
+                write!(f, "Invalid escape sequence {}: {}", escape, message)
+            }
         }
     }
 }
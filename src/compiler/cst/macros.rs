@@ -12,7 +12,7 @@ macro_rules! match_ast {
            match child.node_type() {
                $(
                 AstNodeType::$option => {
-                     guard_empty($holder)?;
+                     guard_empty($holder, child)?;
                      let parse_result = parse_value_of(child)?;
                      let transformed = $transform(parse_result)?;
                      $holder = Some(transformed);
@@ -21,6 +21,7 @@ macro_rules! match_ast {
                _ => {
                    return Err(Error::UnexpectedNode {
                        actual: child.node_type(),
+                       span: child.span().clone(),
                        expected: vec![
                             $(
                                  AstNodeType::$option,
@@ -34,6 +35,7 @@ macro_rules! match_ast {
         $(
         let $holder = $holder.ok_or(Error::MissingContent {
             node_type: AstNodeType::$option,
+            span: $node.span().clone(),
         })?;
         )+
     };
@@ -1,4 +1,4 @@
-pub(crate) use error::*;
+pub use error::*;
 pub(crate) use node::*;
 pub(crate) use parser::*;
 
@@ -1,4 +1,11 @@
 use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use num_bigint::BigInt;
+
+use crate::compiler::span::Span;
+use crate::util::id::HexoId;
+use crate::util::integer_format::IntegerFormat;
 
 #[derive(Clone, Debug)]
 pub(crate) struct CstFile {
@@ -28,13 +35,121 @@ pub(crate) enum CstAtom {
     Hex(u8),
     String(String),
     Number(u32),
+    /// Number literal written in [format], like `10x-2:i8`, already encoded in it
+    Integer {
+        bytes: Vec<u8>,
+        format: IntegerFormat,
+    },
     Constant {
         name: String,
+        span: Span,
     },
     Function {
         name: String,
         params: Vec<CstActualParameter>,
+        span: Span,
+    },
+    /// Marks current position in the output, emits nothing
+    Label {
+        name: String,
+        span: Span,
     },
+    /// Absolute offset of the label in the output, labels defined later in the file are allowed
+    LabelReference {
+        name: String,
+        format: IntegerFormat,
+        span: Span,
+    },
+    /// Compile time integer expression, emitted in [format] or as few big endian bytes as possible
+    Expression {
+        expression: Box<CstExpression>,
+        format: Option<IntegerFormat>,
+        span: Span,
+    },
+    /// Emits of the taken branch of the conditional statement, keeps them in place among other emits
+    Conditional(Rc<CstConditionalStatement>),
+    /// Emits of every iteration of the loop statement
+    Loop(Rc<CstLoopStatement>),
+    /// Checks the output built so far, emits nothing
+    Assertion(Rc<CstAssertStatement>),
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum CstOperator {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Remainder,
+    And,
+    Or,
+    Xor,
+    ShiftLeft,
+    ShiftRight,
+}
+
+impl CstOperator {
+    pub(crate) fn parse(symbol: &str) -> Option<CstOperator> {
+        match symbol {
+            "+" => Some(CstOperator::Add),
+            "-" => Some(CstOperator::Subtract),
+            "*" => Some(CstOperator::Multiply),
+            "/" => Some(CstOperator::Divide),
+            "%" => Some(CstOperator::Remainder),
+            "&" => Some(CstOperator::And),
+            "|" => Some(CstOperator::Or),
+            "^" => Some(CstOperator::Xor),
+            "<<" => Some(CstOperator::ShiftLeft),
+            ">>" => Some(CstOperator::ShiftRight),
+            _ => None,
+        }
+    }
+
+    /// Binding strength of the operator, same as in C: `* / %` > `+ -` > `<< >>` > `&` > `^` > `|`
+    pub(crate) fn precedence(&self) -> u8 {
+        match self {
+            CstOperator::Or => 1,
+            CstOperator::Xor => 2,
+            CstOperator::And => 3,
+            CstOperator::ShiftLeft | CstOperator::ShiftRight => 4,
+            CstOperator::Add | CstOperator::Subtract => 5,
+            CstOperator::Multiply | CstOperator::Divide | CstOperator::Remainder => 6,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) enum CstExpression {
+    Number {
+        value: BigInt,
+        span: Span,
+    },
+    /// Constant or function call, its bytes are read as unsigned big endian integer
+    Atom {
+        atom: CstAtom,
+        span: Span,
+    },
+    Label {
+        name: String,
+        span: Span,
+    },
+    Binary {
+        operator: CstOperator,
+        left: Box<CstExpression>,
+        right: Box<CstExpression>,
+        span: Span,
+    },
+}
+
+impl CstExpression {
+    pub(crate) fn span(&self) -> &Span {
+        match self {
+            CstExpression::Number { span, .. } => span,
+            CstExpression::Atom { span, .. } => span,
+            CstExpression::Label { span, .. } => span,
+            CstExpression::Binary { span, .. } => span,
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -78,11 +193,18 @@ impl CstEmitStatement {
 pub(crate) struct CstConstantStatement {
     name: String,
     atoms: CstAtomVec,
+    overridable: bool,
+    span: Span,
 }
 
 impl CstConstantStatement {
-    pub(crate) fn new(name: String, atoms: CstAtomVec) -> Self {
-        CstConstantStatement { name, atoms }
+    pub(crate) fn new(name: String, atoms: CstAtomVec, overridable: bool, span: Span) -> Self {
+        CstConstantStatement {
+            name,
+            atoms,
+            overridable,
+            span,
+        }
     }
 
     pub(crate) fn name(&self) -> &str {
@@ -92,28 +214,249 @@ impl CstConstantStatement {
     pub(crate) fn atoms(&self) -> &CstAtomVec {
         &self.atoms
     }
+
+    /// Declared with `$?`, so its value may be replaced by a definition without a warning
+    pub(crate) fn is_overridable(&self) -> bool {
+        self.overridable
+    }
+
+    pub(crate) fn span(&self) -> &Span {
+        &self.span
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct CstIncludeStatement {
+    path: String,
+    span: Span,
+}
+
+impl CstIncludeStatement {
+    pub(crate) fn new(path: String, span: Span) -> Self {
+        CstIncludeStatement { path, span }
+    }
+
+    /// Path of the included file, relative paths are resolved against the including file
+    pub(crate) fn path(&self) -> &str {
+        &self.path
+    }
+
+    pub(crate) fn span(&self) -> &Span {
+        &self.span
+    }
+}
+
+/// Parameter declared by a user function, [default] is evaluated when caller omits the argument
+#[derive(Clone, Debug)]
+pub(crate) struct CstFormalParameter {
+    name: String,
+    default: Option<CstAtomVec>,
+}
+
+impl CstFormalParameter {
+    pub(crate) fn new(name: String, default: Option<CstAtomVec>) -> Self {
+        CstFormalParameter { name, default }
+    }
+
+    pub(crate) fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub(crate) fn default(&self) -> Option<&CstAtomVec> {
+        self.default.as_ref()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) enum CstCondition {
+    /// Constant is bound in the current scope, even if its evaluation has failed
+    Defined { name: String },
+    /// Both sides evaluate to the same bytes
+    Equal { left: CstAtomVec, right: CstAtomVec },
+    /// Left side is smaller, both sides are read as unsigned big endian integers
+    Less { left: CstAtomVec, right: CstAtomVec },
+    /// Value has at least one non-zero byte
+    NonZero { value: CstAtomVec },
+    Not(Box<CstCondition>),
+}
+
+/// Branch of a conditional statement, `else` branch has no [condition]
+#[derive(Clone, Debug)]
+pub(crate) struct CstConditionalBranch {
+    condition: Option<CstCondition>,
+    body: CstFunctionStatement,
+    span: Span,
+}
+
+impl CstConditionalBranch {
+    pub(crate) fn new(
+        condition: Option<CstCondition>,
+        body: CstFunctionStatement,
+        span: Span,
+    ) -> Self {
+        CstConditionalBranch {
+            condition,
+            body,
+            span,
+        }
+    }
+
+    pub(crate) fn condition(&self) -> Option<&CstCondition> {
+        self.condition.as_ref()
+    }
+
+    /// Statements of the branch, they join the enclosing scope when the branch is taken
+    pub(crate) fn body(&self) -> &CstFunctionStatement {
+        &self.body
+    }
+
+    pub(crate) fn span(&self) -> &Span {
+        &self.span
+    }
+}
+
+/// `#if` statement, at most one of its [branches] is taken, the first one whose condition holds
+#[derive(Clone, Debug)]
+pub(crate) struct CstConditionalStatement {
+    id: HexoId,
+    branches: Vec<CstConditionalBranch>,
+}
+
+impl CstConditionalStatement {
+    pub(crate) fn new(branches: Vec<CstConditionalBranch>) -> Self {
+        CstConditionalStatement {
+            id: HexoId::next(),
+            branches,
+        }
+    }
+
+    /// Identifies the statement in scopes, the taken branch is recorded under it
+    pub(crate) fn id(&self) -> HexoId {
+        self.id
+    }
+
+    pub(crate) fn branches(&self) -> &Vec<CstConditionalBranch> {
+        &self.branches
+    }
+
+    /// Tells whether any of the branches emits something
+    pub(crate) fn has_emits(&self) -> bool {
+        self.branches
+            .iter()
+            .any(|branch| !branch.body().emits().is_empty())
+    }
+}
+
+/// Values taken by the loop variable
+#[derive(Clone, Debug)]
+pub(crate) enum CstLoopSource {
+    /// Integers from [start] up to, but not including, [end]
+    Range { start: CstAtomVec, end: CstAtomVec },
+    /// Every byte of [value]
+    Bytes { value: CstAtomVec },
+}
+
+/// `#for` statement, [body] is evaluated in a fresh scope on every iteration
+#[derive(Clone, Debug)]
+pub(crate) struct CstLoopStatement {
+    variable: String,
+    source: CstLoopSource,
+    body: CstFunctionStatement,
+    span: Span,
+}
+
+impl CstLoopStatement {
+    pub(crate) fn new(
+        variable: String,
+        source: CstLoopSource,
+        body: CstFunctionStatement,
+        span: Span,
+    ) -> Self {
+        CstLoopStatement {
+            variable,
+            source,
+            body,
+            span,
+        }
+    }
+
+    /// Name of the constant bound to the current value
+    pub(crate) fn variable(&self) -> &str {
+        &self.variable
+    }
+
+    pub(crate) fn source(&self) -> &CstLoopSource {
+        &self.source
+    }
+
+    pub(crate) fn body(&self) -> &CstFunctionStatement {
+        &self.body
+    }
+
+    /// Span of the values iterated over
+    pub(crate) fn span(&self) -> &Span {
+        &self.span
+    }
+}
+
+/// `#assert` statement, fails compilation with [message] when [condition] doesn't hold
+#[derive(Clone, Debug)]
+pub(crate) struct CstAssertStatement {
+    condition: CstCondition,
+    message: Option<CstAtomVec>,
+    span: Span,
+}
+
+impl CstAssertStatement {
+    pub(crate) fn new(condition: CstCondition, message: Option<CstAtomVec>, span: Span) -> Self {
+        CstAssertStatement {
+            condition,
+            message,
+            span,
+        }
+    }
+
+    pub(crate) fn condition(&self) -> &CstCondition {
+        &self.condition
+    }
+
+    /// Evaluated only when the assertion fails
+    pub(crate) fn message(&self) -> Option<&CstAtomVec> {
+        self.message.as_ref()
+    }
+
+    pub(crate) fn span(&self) -> &Span {
+        &self.span
+    }
 }
 
 #[derive(Clone, Debug)]
 pub(crate) struct CstFunctionStatement {
     name: String,
+    /// Declared parameters, functions without declaration accept any arguments as `$0`, `$1`, ...
+    params: Option<Vec<CstFormalParameter>>,
     emits: Vec<CstEmitStatement>,
     functions: Vec<CstFunctionStatement>,
-    constants: Vec<CstConstantStatement>,
+    declarations: Vec<CstDeclaration>,
+    includes: Vec<CstIncludeStatement>,
 }
 
 impl CstFunctionStatement {
     pub(crate) fn new(
         name: String,
+        params: Option<Vec<CstFormalParameter>>,
         emits: Vec<CstEmitStatement>,
         functions: Vec<CstFunctionStatement>,
-        constants: Vec<CstConstantStatement>,
+        declarations: Vec<CstDeclaration>,
+        includes: Vec<CstIncludeStatement>,
     ) -> Self {
         CstFunctionStatement {
             name,
+            params,
             emits,
             functions,
-            constants,
+            declarations,
+            includes,
         }
     }
 
@@ -121,15 +464,32 @@ impl CstFunctionStatement {
         &self.name
     }
 
+    pub(crate) fn params(&self) -> Option<&Vec<CstFormalParameter>> {
+        self.params.as_ref()
+    }
+
     pub(crate) fn emits(&self) -> &Vec<CstEmitStatement> {
         &self.emits
     }
 
-    pub(crate) fn constants(&self) -> &Vec<CstConstantStatement> {
-        &self.constants
+    /// Constants and conditionals in the order they are written
+    pub(crate) fn declarations(&self) -> &Vec<CstDeclaration> {
+        &self.declarations
     }
 
     pub(crate) fn functions(&self) -> &Vec<CstFunctionStatement> {
         &self.functions
     }
+
+    pub(crate) fn includes(&self) -> &Vec<CstIncludeStatement> {
+        &self.includes
+    }
+}
+
+/// Statement evaluated when its scope is built, each of them sees only the preceding ones
+#[derive(Clone, Debug)]
+pub(crate) enum CstDeclaration {
+    Constant(CstConstantStatement),
+    /// Statements of the taken branch are declared in place of the conditional
+    Conditional(Rc<CstConditionalStatement>),
 }
@@ -1,78 +1,147 @@
+use std::iter::Peekable;
+use std::ops::Range;
 use std::path::Path;
+use std::rc::Rc;
+use std::str::CharIndices;
+
+use num_bigint::BigInt;
 
 use crate::compiler::ast::{AstNode, AstNodeType};
 use crate::compiler::cst::CstFile;
 use crate::compiler::cst::Error;
 use crate::compiler::cst::{
-    CstActualParameter, CstAtom, CstConstantStatement, CstEmitStatement, CstFunctionStatement,
+    CstActualParameter, CstAssertStatement, CstAtom, CstAtomVec, CstCondition,
+    CstConditionalBranch, CstConditionalStatement, CstConstantStatement, CstDeclaration,
+    CstEmitStatement, CstExpression, CstFormalParameter, CstFunctionStatement, CstIncludeStatement,
+    CstLoopSource, CstLoopStatement, CstOperator,
 };
 use crate::match_ast;
+use crate::util::integer_format::IntegerFormat;
 use crate::util::logger;
 
 #[derive(Default)]
 pub(crate) struct CstParser {}
 
 impl CstParser {
-    pub(crate) fn parse(&self, path: &Path, ast_root: AstNode) -> Result<CstFile, Error> {
+    /// Parses [ast_root] into CST, reporting errors of every malformed statement
+    pub(crate) fn parse(&self, path: &Path, ast_root: AstNode) -> Result<CstFile, Vec<Error>> {
         logger::debug!("Parsing CST of {:?}", path);
         parse_file(path, &ast_root)
     }
+
+    /// Parses [ast_root] of `name=value` definition into constant statement
+    pub(crate) fn parse_definition(
+        &self,
+        ast_root: AstNode,
+    ) -> Result<CstConstantStatement, Error> {
+        guard_node_type(&ast_root, AstNodeType::File)?;
+        let statement = ast_root.children().first().ok_or(Error::MissingContent {
+            node_type: AstNodeType::StatementConst,
+            span: ast_root.span().clone(),
+        })?;
+
+        parse_constant(statement)
+    }
 }
 
 const MAIN_FUNCTION_NAME: &str = "main";
+const BRANCH_FUNCTION_NAME: &str = "if";
+const LOOP_FUNCTION_NAME: &str = "for";
 
-fn parse_file(path: &Path, node: &AstNode) -> Result<CstFile, Error> {
-    guard_node_type(node, AstNodeType::File)?;
-    let (emits, functions, constants) = parse_function_body(node)?;
+fn parse_file(path: &Path, node: &AstNode) -> Result<CstFile, Vec<Error>> {
+    guard_node_type(node, AstNodeType::File).map_err(|e| vec![e])?;
+
+    let mut errors = Vec::new();
+    let (emits, functions, declarations, includes) = parse_function_body(node, &mut errors);
+    if !errors.is_empty() {
+        return Err(errors);
+    }
 
     Ok(CstFile::new(
         path,
-        CstFunctionStatement::new(MAIN_FUNCTION_NAME.to_string(), emits, functions, constants),
+        CstFunctionStatement::new(
+            MAIN_FUNCTION_NAME.to_string(),
+            None,
+            emits,
+            functions,
+            declarations,
+            includes,
+        ),
     ))
 }
 
 type BodyParsingResult = (
     Vec<CstEmitStatement>,
     Vec<CstFunctionStatement>,
-    Vec<CstConstantStatement>,
+    Vec<CstDeclaration>,
+    Vec<CstIncludeStatement>,
 );
 
-fn parse_function_body(node: &AstNode) -> Result<BodyParsingResult, Error> {
+/// Parses all statements of the body, errors are collected into [errors] and malformed statements are skipped
+fn parse_function_body(node: &AstNode, errors: &mut Vec<Error>) -> BodyParsingResult {
     let mut emits = Vec::new();
     let mut functions = Vec::new();
-    let mut constants = Vec::new();
+    let mut declarations = Vec::new();
+    let mut includes = Vec::new();
 
     for child in node.children() {
-        match child.node_type() {
-            AstNodeType::StatementConst => constants.push(parse_constant(child)?),
-            AstNodeType::StatementEmit => emits.push(parse_emit_statement(child)?),
-            AstNodeType::StatementFn => functions.push(parse_function(child)?),
-            _ => {
-                return Err(Error::UnexpectedNode {
-                    actual: child.node_type(),
-                    expected: vec![
-                        AstNodeType::StatementConst,
-                        AstNodeType::StatementEmit,
-                        AstNodeType::StatementFn,
-                    ],
-                })
+        let result = match child.node_type() {
+            AstNodeType::StatementConst => {
+                parse_constant(child).map(|c| declarations.push(CstDeclaration::Constant(c)))
             }
+            AstNodeType::StatementEmit => parse_emit_statement(child).map(|e| emits.push(e)),
+            AstNodeType::StatementFn => parse_function(child, errors).map(|f| functions.push(f)),
+            AstNodeType::StatementInclude => parse_include(child).map(|i| includes.push(i)),
+            AstNodeType::StatementLabel => parse_label(child).map(|e| emits.push(e)),
+            AstNodeType::StatementIf => parse_conditional(child, errors).map(|c| {
+                let conditional = Rc::new(c);
+                if conditional.has_emits() {
+                    emits.push(CstEmitStatement::new(vec![CstAtom::Conditional(
+                        conditional.clone(),
+                    )]));
+                }
+                declarations.push(CstDeclaration::Conditional(conditional));
+            }),
+            AstNodeType::StatementFor => parse_loop(child, errors)
+                .map(|l| emits.push(CstEmitStatement::new(vec![CstAtom::Loop(Rc::new(l))]))),
+            AstNodeType::StatementAssert => parse_assertion(child)
+                .map(|a| emits.push(CstEmitStatement::new(vec![CstAtom::Assertion(Rc::new(a))]))),
+            _ => Err(Error::UnexpectedNode {
+                actual: child.node_type(),
+                span: child.span().clone(),
+                expected: vec![
+                    AstNodeType::StatementConst,
+                    AstNodeType::StatementEmit,
+                    AstNodeType::StatementFn,
+                    AstNodeType::StatementInclude,
+                    AstNodeType::StatementLabel,
+                    AstNodeType::StatementIf,
+                    AstNodeType::StatementFor,
+                    AstNodeType::StatementAssert,
+                ],
+            }),
+        };
+
+        if let Err(error) = result {
+            errors.push(error);
         }
     }
 
-    Ok((emits, functions, constants))
+    (emits, functions, declarations, includes)
 }
 
 fn parse_constant(node: &AstNode) -> Result<CstConstantStatement, Error> {
     guard_node_type(node, AstNodeType::StatementConst)?;
     let mut atom_buff = Vec::new();
     let mut name = None;
+    let mut overridable = false;
 
     for child in node.children() {
         match child.node_type() {
             AstNodeType::StatementConstName => {
                 name = Some(parse_value_of(child)?);
             }
+            AstNodeType::StatementConstOverridable => overridable = true,
             _ => parse_atom_into(child, &mut atom_buff)?,
         }
     }
@@ -80,33 +149,49 @@ fn parse_constant(node: &AstNode) -> Result<CstConstantStatement, Error> {
     Ok(CstConstantStatement::new(
         name.ok_or(Error::MissingContent {
             node_type: AstNodeType::StatementConstName,
+            span: node.span().clone(),
         })?
         .to_string(),
         atom_buff,
+        overridable,
+        node.span().clone(),
     ))
 }
 
-fn parse_function(node: &AstNode) -> Result<CstFunctionStatement, Error> {
+fn parse_function(node: &AstNode, errors: &mut Vec<Error>) -> Result<CstFunctionStatement, Error> {
     let mut name = None;
+    let mut params = None;
     let mut emits = None;
     let mut functions = None;
-    let mut constants = None;
+    let mut declarations = None;
+    let mut includes = None;
 
     for child in node.children() {
         match child.node_type() {
             AstNodeType::StatementFnName => {
                 name = Some(parse_value_of(child)?);
             }
+            AstNodeType::StatementFnParams => {
+                guard_empty(params.as_ref(), child)?;
+                params = Some(parse_function_params(child)?);
+            }
             AstNodeType::StatementFnBody => {
-                let (emits_r, functions_r, constants_r) = parse_function_body(child)?;
+                let (emits_r, functions_r, declarations_r, includes_r) =
+                    parse_function_body(child, errors);
                 emits = Some(emits_r);
                 functions = Some(functions_r);
-                constants = Some(constants_r);
+                declarations = Some(declarations_r);
+                includes = Some(includes_r);
             }
             _ => {
                 return Err(Error::UnexpectedNode {
                     actual: child.node_type(),
-                    expected: vec![AstNodeType::StatementFnName, AstNodeType::StatementFnBody],
+                    span: child.span().clone(),
+                    expected: vec![
+                        AstNodeType::StatementFnName,
+                        AstNodeType::StatementFnParams,
+                        AstNodeType::StatementFnBody,
+                    ],
                 })
             }
         }
@@ -115,13 +200,373 @@ fn parse_function(node: &AstNode) -> Result<CstFunctionStatement, Error> {
     Ok(CstFunctionStatement::new(
         name.ok_or(Error::MissingContent {
             node_type: AstNodeType::StatementFnName,
+            span: node.span().clone(),
         })?,
+        params,
         emits.unwrap_or(Vec::new()),
         functions.unwrap_or(Vec::new()),
-        constants.unwrap_or(Vec::new()),
+        declarations.unwrap_or(Vec::new()),
+        includes.unwrap_or(Vec::new()),
+    ))
+}
+
+/// Parses `#if` statement, every branch body is parsed even though at most one of them is taken
+fn parse_conditional(
+    node: &AstNode,
+    errors: &mut Vec<Error>,
+) -> Result<CstConditionalStatement, Error> {
+    guard_node_type(node, AstNodeType::StatementIf)?;
+    let mut branches = Vec::new();
+    let mut condition = None;
+
+    for child in node.children() {
+        match child.node_type() {
+            AstNodeType::IfCondition => {
+                guard_empty(condition.as_ref(), child)?;
+                condition = Some((parse_condition(child)?, child.span().clone()));
+            }
+            AstNodeType::IfBody => {
+                let (emits, functions, declarations, includes) =
+                    parse_function_body(child, errors);
+                let body = CstFunctionStatement::new(
+                    BRANCH_FUNCTION_NAME.to_string(),
+                    None,
+                    emits,
+                    functions,
+                    declarations,
+                    includes,
+                );
+
+                // `else` branch is spanned by its body
+                let (condition, span) = match condition.take() {
+                    Some((condition, span)) => (Some(condition), span),
+                    None => (None, child.span().clone()),
+                };
+                branches.push(CstConditionalBranch::new(condition, body, span));
+            }
+            _ => {
+                return Err(Error::UnexpectedNode {
+                    actual: child.node_type(),
+                    span: child.span().clone(),
+                    expected: vec![AstNodeType::IfCondition, AstNodeType::IfBody],
+                })
+            }
+        }
+    }
+
+    Ok(CstConditionalStatement::new(branches))
+}
+
+/// Loop is represented as emit statement of a single [CstAtom::Loop], its body can't declare
+/// anything visible outside of the iteration
+fn parse_loop(node: &AstNode, errors: &mut Vec<Error>) -> Result<CstLoopStatement, Error> {
+    guard_node_type(node, AstNodeType::StatementFor)?;
+    let mut variable = None;
+    let mut source = None;
+    let mut body = None;
+
+    for child in node.children() {
+        match child.node_type() {
+            AstNodeType::ForVariable => {
+                guard_empty(variable.as_ref(), child)?;
+                variable = Some(parse_value_of(child)?);
+            }
+            AstNodeType::ForRange => {
+                guard_empty(source.as_ref(), child)?;
+                let mut bounds = Vec::new();
+                for bound in child.children() {
+                    guard_node_type(bound, AstNodeType::ForRangeValue)?;
+                    bounds.push(parse_condition_value(bound)?);
+                }
+                let (Some(end), Some(start)) = (bounds.pop(), bounds.pop()) else {
+                    return Err(Error::MissingContent {
+                        node_type: AstNodeType::ForRangeValue,
+                        span: child.span().clone(),
+                    });
+                };
+                source = Some((CstLoopSource::Range { start, end }, child.span().clone()));
+            }
+            AstNodeType::ForValues => {
+                guard_empty(source.as_ref(), child)?;
+                let value = parse_condition_value(child)?;
+                source = Some((CstLoopSource::Bytes { value }, child.span().clone()));
+            }
+            AstNodeType::ForBody => {
+                guard_empty(body.as_ref(), child)?;
+                let (emits, functions, declarations, includes) =
+                    parse_function_body(child, errors);
+                body = Some(CstFunctionStatement::new(
+                    LOOP_FUNCTION_NAME.to_string(),
+                    None,
+                    emits,
+                    functions,
+                    declarations,
+                    includes,
+                ));
+            }
+            _ => {
+                return Err(Error::UnexpectedNode {
+                    actual: child.node_type(),
+                    span: child.span().clone(),
+                    expected: vec![
+                        AstNodeType::ForVariable,
+                        AstNodeType::ForRange,
+                        AstNodeType::ForValues,
+                        AstNodeType::ForBody,
+                    ],
+                })
+            }
+        }
+    }
+
+    let missing = |node_type| Error::MissingContent {
+        node_type,
+        span: node.span().clone(),
+    };
+    let (source, span) = source.ok_or_else(|| missing(AstNodeType::ForValues))?;
+
+    Ok(CstLoopStatement::new(
+        variable.ok_or_else(|| missing(AstNodeType::ForVariable))?,
+        source,
+        body.ok_or_else(|| missing(AstNodeType::ForBody))?,
+        span,
     ))
 }
 
+/// Assertion is represented as emit statement of a single [CstAtom::Assertion], so it is checked
+/// against the output built up to its position
+fn parse_assertion(node: &AstNode) -> Result<CstAssertStatement, Error> {
+    guard_node_type(node, AstNodeType::StatementAssert)?;
+    let mut condition = None;
+    let mut message = None;
+
+    for child in node.children() {
+        match child.node_type() {
+            AstNodeType::IfCondition => {
+                guard_empty(condition.as_ref(), child)?;
+                condition = Some(parse_condition(child)?);
+            }
+            AstNodeType::AssertMessage => {
+                guard_empty(message.as_ref(), child)?;
+                message = Some(parse_condition_value(child)?);
+            }
+            _ => {
+                return Err(Error::UnexpectedNode {
+                    actual: child.node_type(),
+                    span: child.span().clone(),
+                    expected: vec![AstNodeType::IfCondition, AstNodeType::AssertMessage],
+                })
+            }
+        }
+    }
+
+    Ok(CstAssertStatement::new(
+        condition.ok_or_else(|| Error::MissingContent {
+            node_type: AstNodeType::IfCondition,
+            span: node.span().clone(),
+        })?,
+        message,
+        node.span().clone(),
+    ))
+}
+
+fn parse_condition(node: &AstNode) -> Result<CstCondition, Error> {
+    guard_node_type(node, AstNodeType::IfCondition)?;
+    let mut negated = false;
+    let mut condition = None;
+
+    for child in node.children() {
+        match child.node_type() {
+            AstNodeType::IfConditionNot => negated = true,
+            AstNodeType::IfConditionDefined => {
+                guard_empty(condition.as_ref(), child)?;
+                match_ast!(
+                    child => IfConditionDefined,
+                    AtomConst => name | Ok
+                );
+                condition = Some(CstCondition::Defined { name });
+            }
+            AstNodeType::IfConditionCompare => {
+                guard_empty(condition.as_ref(), child)?;
+                condition = Some(parse_comparison(child)?);
+            }
+            AstNodeType::IfConditionValue => {
+                guard_empty(condition.as_ref(), child)?;
+                condition = Some(CstCondition::NonZero {
+                    value: parse_condition_value(child)?,
+                });
+            }
+            _ => {
+                return Err(Error::UnexpectedNode {
+                    actual: child.node_type(),
+                    span: child.span().clone(),
+                    expected: vec![
+                        AstNodeType::IfConditionNot,
+                        AstNodeType::IfConditionDefined,
+                        AstNodeType::IfConditionCompare,
+                        AstNodeType::IfConditionValue,
+                    ],
+                })
+            }
+        }
+    }
+
+    let condition = condition.ok_or_else(|| Error::MissingContent {
+        node_type: AstNodeType::IfConditionValue,
+        span: node.span().clone(),
+    })?;
+
+    Ok(match negated {
+        true => CstCondition::Not(Box::new(condition)),
+        false => condition,
+    })
+}
+
+fn parse_comparison(node: &AstNode) -> Result<CstCondition, Error> {
+    let mut values = Vec::new();
+    let mut operator = None;
+
+    for child in node.children() {
+        match child.node_type() {
+            AstNodeType::IfConditionValue => values.push(parse_condition_value(child)?),
+            AstNodeType::IfConditionOperator => operator = Some(parse_value_of(child)?),
+            _ => {
+                return Err(Error::UnexpectedNode {
+                    actual: child.node_type(),
+                    span: child.span().clone(),
+                    expected: vec![
+                        AstNodeType::IfConditionValue,
+                        AstNodeType::IfConditionOperator,
+                    ],
+                })
+            }
+        }
+    }
+
+    let (Some(right), Some(left)) = (values.pop(), values.pop()) else {
+        return Err(Error::MissingContent {
+            node_type: AstNodeType::IfConditionValue,
+            span: node.span().clone(),
+        });
+    };
+    let not = |condition| CstCondition::Not(Box::new(condition));
+
+    // every ordering is expressed with `<`, swapping or negating its operands
+    match operator.as_deref() {
+        Some("==") => Ok(CstCondition::Equal { left, right }),
+        Some("!=") => Ok(not(CstCondition::Equal { left, right })),
+        Some("<") => Ok(CstCondition::Less { left, right }),
+        Some(">") => Ok(CstCondition::Less {
+            left: right,
+            right: left,
+        }),
+        Some("<=") => Ok(not(CstCondition::Less {
+            left: right,
+            right: left,
+        })),
+        Some(">=") => Ok(not(CstCondition::Less { left, right })),
+        _ => Err(Error::MissingContent {
+            node_type: AstNodeType::IfConditionOperator,
+            span: node.span().clone(),
+        }),
+    }
+}
+
+/// Atoms of a condition operand or of a loop source
+fn parse_condition_value(node: &AstNode) -> Result<CstAtomVec, Error> {
+    let mut atoms = Vec::new();
+    for child in node.children() {
+        parse_atom_into(child, &mut atoms)?;
+    }
+
+    Ok(atoms)
+}
+
+fn parse_function_params(node: &AstNode) -> Result<Vec<CstFormalParameter>, Error> {
+    guard_node_type(node, AstNodeType::StatementFnParams)?;
+    let mut params: Vec<CstFormalParameter> = Vec::new();
+
+    for child in node.children() {
+        guard_node_type(child, AstNodeType::StatementFnParam)?;
+        let mut name = None;
+        let mut default = None;
+
+        for p_child in child.children() {
+            match p_child.node_type() {
+                AstNodeType::StatementFnParamName => {
+                    guard_empty(name.as_ref(), p_child)?;
+                    name = Some(parse_value_of(p_child)?);
+                }
+                AstNodeType::StatementFnParamDefault => {
+                    guard_empty(default.as_ref(), p_child)?;
+                    let mut atoms = Vec::new();
+                    for value_node in p_child.children() {
+                        parse_atom_into(value_node, &mut atoms)?;
+                    }
+                    default = Some(atoms);
+                }
+                _ => {
+                    return Err(Error::UnexpectedNode {
+                        actual: p_child.node_type(),
+                        span: p_child.span().clone(),
+                        expected: vec![
+                            AstNodeType::StatementFnParamName,
+                            AstNodeType::StatementFnParamDefault,
+                        ],
+                    })
+                }
+            }
+        }
+
+        let name = name.ok_or_else(|| Error::MissingContent {
+            node_type: AstNodeType::StatementFnParamName,
+            span: child.span().clone(),
+        })?;
+
+        if params.iter().any(|param| param.name() == name) {
+            return Err(Error::MalformedNodeValue {
+                message: format!("parameter {} is declared more than once", name),
+                span: child.span().clone(),
+            });
+        }
+
+        params.push(CstFormalParameter::new(name, default));
+    }
+
+    Ok(params)
+}
+
+fn parse_include(node: &AstNode) -> Result<CstIncludeStatement, Error> {
+    guard_node_type(node, AstNodeType::StatementInclude)?;
+
+    let mut path = None;
+    for child in node.children() {
+        guard_empty(path.as_ref(), child)?;
+        path = Some(parse_string(child)?);
+    }
+
+    Ok(CstIncludeStatement::new(
+        path.ok_or_else(|| Error::MissingContent {
+            node_type: AstNodeType::AtomUtf8,
+            span: node.span().clone(),
+        })?,
+        node.span().clone(),
+    ))
+}
+
+/// Label is represented as emit statement of a single zero-width [CstAtom::Label]
+fn parse_label(node: &AstNode) -> Result<CstEmitStatement, Error> {
+    match_ast!(
+        node => StatementLabel,
+        StatementLabelName => name | Ok
+    );
+
+    Ok(CstEmitStatement::new(vec![CstAtom::Label {
+        name,
+        span: node.span().clone(),
+    }]))
+}
+
 fn parse_emit_statement(node: &AstNode) -> Result<CstEmitStatement, Error> {
     guard_node_type(node, AstNodeType::StatementEmit)?;
     let mut atoms = Vec::new();
@@ -136,19 +581,24 @@ fn parse_emit_statement(node: &AstNode) -> Result<CstEmitStatement, Error> {
 fn parse_atom_into(node: &AstNode, buff: &mut Vec<CstAtom>) -> Result<(), Error> {
     match node.node_type() {
         AstNodeType::AtomHex => parse_atom_hex_into(node, buff)?,
-        AstNodeType::AtomUtf8 => parse_atom_utf8_into(node, buff)?,
+        AstNodeType::AtomUtf8 | AstNodeType::AtomUtf8Raw => parse_atom_utf8_into(node, buff)?,
         AstNodeType::AtomBaseNumber => parse_atom_base_num_into(node, buff)?,
         AstNodeType::AtomConst => parse_atom_constant_into(node, buff)?,
+        AstNodeType::AtomLabel => parse_atom_label_into(node, buff)?,
         AstNodeType::AtomFn => parse_atom_function_into(node, buff)?,
+        AstNodeType::AtomExpr => parse_atom_expression_into(node, buff)?,
         _ => {
             return Err(Error::UnexpectedNode {
                 actual: node.node_type(),
+                span: node.span().clone(),
                 expected: vec![
                     AstNodeType::AtomHex,
                     AstNodeType::AtomUtf8,
                     AstNodeType::AtomBaseNumber,
                     AstNodeType::AtomConst,
+                    AstNodeType::AtomLabel,
                     AstNodeType::AtomFn,
+                    AstNodeType::AtomExpr,
                 ],
             })
         }
@@ -160,13 +610,63 @@ fn parse_atom_into(node: &AstNode, buff: &mut Vec<CstAtom>) -> Result<(), Error>
 fn parse_atom_constant_into(node: &AstNode, buf: &mut Vec<CstAtom>) -> Result<(), Error> {
     guard_node_type(node, AstNodeType::AtomConst)?;
     let content = parse_value_of(node)?;
-    let atom = CstAtom::Constant { name: content };
+    let atom = CstAtom::Constant {
+        name: content,
+        span: node.span().clone(),
+    };
 
     buf.push(atom);
 
     Ok(())
 }
 
+fn parse_atom_label_into(node: &AstNode, buf: &mut Vec<CstAtom>) -> Result<(), Error> {
+    guard_node_type(node, AstNodeType::AtomLabel)?;
+    let mut name = None;
+    let mut format = None;
+
+    for child in node.children() {
+        match child.node_type() {
+            AstNodeType::AtomLabelName => {
+                guard_empty(name.as_ref(), child)?;
+                name = Some(parse_value_of(child)?);
+            }
+            AstNodeType::AtomIntFormat => {
+                guard_empty(format, child)?;
+                format = Some(parse_int_format(child)?);
+            }
+            _ => {
+                return Err(Error::UnexpectedNode {
+                    actual: child.node_type(),
+                    span: child.span().clone(),
+                    expected: vec![AstNodeType::AtomLabelName, AstNodeType::AtomIntFormat],
+                })
+            }
+        }
+    }
+
+    buf.push(CstAtom::LabelReference {
+        name: name.ok_or_else(|| Error::MissingContent {
+            node_type: AstNodeType::AtomLabelName,
+            span: node.span().clone(),
+        })?,
+        format: format.unwrap_or(IntegerFormat::U32_BE),
+        span: node.span().clone(),
+    });
+
+    Ok(())
+}
+
+fn parse_int_format(node: &AstNode) -> Result<IntegerFormat, Error> {
+    guard_node_type(node, AstNodeType::AtomIntFormat)?;
+    let content = parse_value_of(node)?;
+
+    IntegerFormat::parse(content.as_str()).ok_or_else(|| Error::MalformedNodeValue {
+        message: format!("unknown integer format {}", content),
+        span: node.span().clone(),
+    })
+}
+
 fn parse_atom_function_into(node: &AstNode, buf: &mut Vec<CstAtom>) -> Result<(), Error> {
     guard_node_type(node, AstNodeType::AtomFn)?;
     let mut name = None;
@@ -175,13 +675,14 @@ fn parse_atom_function_into(node: &AstNode, buf: &mut Vec<CstAtom>) -> Result<()
     for child in node.children() {
         match child.node_type() {
             AstNodeType::AtomFnName => {
-                guard_empty(name)?;
+                guard_empty(name, child)?;
                 name = Some(parse_value_of(child)?);
             }
             AstNodeType::AtomFnParams => params = Some(parse_atom_fn_params(child)?),
             _ => {
                 return Err(Error::UnexpectedNode {
                     actual: child.node_type(),
+                    span: child.span().clone(),
                     expected: vec![AstNodeType::AtomFnName, AstNodeType::AtomFnParams],
                 })
             }
@@ -190,6 +691,7 @@ fn parse_atom_function_into(node: &AstNode, buf: &mut Vec<CstAtom>) -> Result<()
 
     let name_value = name.ok_or(Error::MissingContent {
         node_type: AstNodeType::AtomFnName,
+        span: node.span().clone(),
     })?;
 
     let params_value = params.unwrap_or(Vec::new());
@@ -197,6 +699,7 @@ fn parse_atom_function_into(node: &AstNode, buf: &mut Vec<CstAtom>) -> Result<()
     buf.push(CstAtom::Function {
         name: name_value,
         params: params_value,
+        span: node.span().clone(),
     });
 
     Ok(())
@@ -225,6 +728,7 @@ fn parse_atom_fn_params(node: &AstNode) -> Result<Vec<CstActualParameter>, Error
                 _ => {
                     return Err(Error::UnexpectedNode {
                         actual: p_child.node_type(),
+                        span: p_child.span().clone(),
                         expected: vec![AstNodeType::AtomFnParamValue],
                     })
                 }
@@ -245,73 +749,404 @@ fn parse_atom_hex_into(node: &AstNode, buf: &mut Vec<CstAtom>) -> Result<(), Err
 
     let content = node.content().ok_or(Error::MissingContent {
         node_type: AstNodeType::AtomHex,
+        span: node.span().clone(),
     })?;
 
-    let bytes = decode_bytes_from_string(content.as_str())?;
+    let bytes =
+        decode_bytes_from_string(content.as_str()).map_err(|message| Error::MalformedNodeValue {
+            message,
+            span: node.span().clone(),
+        })?;
     for byte in bytes {
         buf.push(CstAtom::Hex(byte))
     }
     Ok(())
 }
 
-pub(crate) fn decode_bytes_from_string(s: &str) -> Result<Vec<u8>, Error> {
+pub(crate) fn decode_bytes_from_string(s: &str) -> Result<Vec<u8>, String> {
+    if !s.len().is_multiple_of(2) {
+        return Err(format!("can't parse bytes {}, odd number of digits", s));
+    }
+
     (0..s.len())
         .step_by(2)
         .map(|i| {
-            if s.len() < 2 {
-                return Err(Error::MalformedNodeValue {
-                    message: format!("can't parse bytes {}", s),
-                });
-            }
-            u8::from_str_radix(&s[i..i + 2], 16).map_err(|_| Error::MalformedNodeValue {
-                message: format!("can't parse bytes {}", s),
-            })
+            s.get(i..i + 2)
+                .and_then(|byte| u8::from_str_radix(byte, 16).ok())
+                .ok_or_else(|| format!("can't parse bytes {}", s))
         })
         .collect()
 }
 
 fn parse_atom_utf8_into(node: &AstNode, buf: &mut Vec<CstAtom>) -> Result<(), Error> {
-    guard_node_type(node, AstNodeType::AtomUtf8)?;
-    let content = parse_value_of(node)?;
-    buf.push(CstAtom::String(content));
+    buf.push(CstAtom::String(parse_string(node)?));
 
     Ok(())
 }
 
-fn parse_atom_base_num_into(node: &AstNode, buf: &mut Vec<CstAtom>) -> Result<(), Error> {
-    fn parse_number_base(base: String) -> Result<u32, Error> {
-        let base_value = base.parse().map_err(|_| Error::MalformedNodeValue {
-            message: format!("can't parse base {}", base),
-        })?;
+/// Content of a string literal, escape sequences are decoded unless the literal is raw
+fn parse_string(node: &AstNode) -> Result<String, Error> {
+    match node.node_type() {
+        AstNodeType::AtomUtf8Raw => parse_value_of(node),
+        _ => {
+            guard_node_type(node, AstNodeType::AtomUtf8)?;
+            let content = parse_value_of(node)?;
+            decode_escapes(&content).map_err(|(range, message)| Error::InvalidEscape {
+                escape: content[range.clone()].to_string(),
+                message,
+                span: node.span().slice(range.start, range.end),
+            })
+        }
+    }
+}
+
+/// Decodes `\n \r \t \0 \\ \' \" \xNN \u{N..}` and doubled quote,
+/// on error returns byte range of the offending escape
+fn decode_escapes(content: &str) -> Result<String, (Range<usize>, String)> {
+    fn take_hex_digits(chars: &mut Peekable<CharIndices>, limit: usize) -> String {
+        let mut digits = String::new();
+        while let Some((_, c)) = chars.next_if(|(_, c)| c.is_ascii_hexdigit() && digits.len() < limit) {
+            digits.push(c);
+        }
+        digits
+    }
+
+    let mut decoded = String::with_capacity(content.len());
+    let mut chars = content.char_indices().peekable();
+
+    while let Some((start, character)) = chars.next() {
+        if character == '\'' {
+            // grammar only lets quotes in doubled
+            chars.next();
+            decoded.push('\'');
+            continue;
+        }
+        if character != '\\' {
+            decoded.push(character);
+            continue;
+        }
+
+        let Some((_, kind)) = chars.next() else {
+            return Err((start..content.len(), "escape is not finished".to_string()));
+        };
+        let escaped = match kind {
+            'n' => '\n',
+            'r' => '\r',
+            't' => '\t',
+            '0' => '\0',
+            '\\' | '\'' | '"' => kind,
+            'x' => {
+                let digits = take_hex_digits(&mut chars, 2);
+                let end = start + 2 + digits.len();
+                match u8::from_str_radix(&digits, 16) {
+                    Ok(byte) if digits.len() == 2 && byte <= 0x7F => byte as char,
+                    Ok(_) if digits.len() == 2 => {
+                        return Err((
+                            start..end,
+                            "only values up to 7F are allowed, use \\u{..} for characters \
+                            or hex atoms for raw bytes"
+                                .to_string(),
+                        ))
+                    }
+                    _ => return Err((start..end, "expected two hex digits".to_string())),
+                }
+            }
+            'u' => {
+                let opened = chars.next_if(|(_, c)| *c == '{').is_some();
+                let digits = take_hex_digits(&mut chars, 6);
+                let closed = opened && chars.next_if(|(_, c)| *c == '}').is_some();
+                let end = start + 2 + opened as usize + digits.len() + closed as usize;
+                if !closed || digits.is_empty() {
+                    return Err((start..end, "expected 1 to 6 hex digits in braces".to_string()));
+                }
+                u32::from_str_radix(&digits, 16)
+                    .ok()
+                    .and_then(char::from_u32)
+                    .ok_or_else(|| (start..end, "not a unicode scalar value".to_string()))?
+            }
+            _ => {
+                let end = start + 1 + kind.len_utf8();
+                return Err((start..end, "unknown escape".to_string()));
+            }
+        };
+        decoded.push(escaped);
+    }
+
+    Ok(decoded)
+}
+
+/// Number literal like `16xcafe:u32`, [digits] are kept as written for error messages
+struct BaseNumber {
+    base: u32,
+    digits: String,
+    value: BigInt,
+    format: Option<IntegerFormat>,
+}
+
+fn parse_base_number(node: &AstNode) -> Result<BaseNumber, Error> {
+    fn parse_number_base(base: String, node: &AstNode) -> Result<u32, Error> {
+        let base_value = base
+            .parse()
+            .ok()
+            .filter(|base| (2..=36).contains(base))
+            .ok_or_else(|| Error::MalformedNodeValue {
+                message: format!("can't parse base {}", base),
+                span: node.span().clone(),
+            })?;
         Ok(base_value)
     }
 
-    match_ast!(
-        node => AtomBaseNumber,
-        AtomBaseNumberBase => base | parse_number_base
-        AtomBaseNumberValue => value | Ok
-    );
+    guard_node_type(node, AstNodeType::AtomBaseNumber)?;
+    let mut base = None;
+    let mut digits = None;
+    let mut format = None;
 
-    buf.push(CstAtom::Number(
-        u32::from_str_radix(value.as_str(), base).map_err(|_| Error::MalformedNodeValue {
-            message: format!("can't parse number {}", value),
-        })?,
-    ));
+    for child in node.children() {
+        match child.node_type() {
+            AstNodeType::AtomBaseNumberBase => {
+                guard_empty(base, child)?;
+                base = Some(parse_number_base(parse_value_of(child)?, child)?);
+            }
+            AstNodeType::AtomBaseNumberValue => {
+                guard_empty(digits.as_ref(), child)?;
+                digits = Some(parse_value_of(child)?);
+            }
+            AstNodeType::AtomIntFormat => {
+                guard_empty(format, child)?;
+                format = Some(parse_int_format(child)?);
+            }
+            _ => {
+                return Err(Error::UnexpectedNode {
+                    actual: child.node_type(),
+                    span: child.span().clone(),
+                    expected: vec![
+                        AstNodeType::AtomBaseNumberBase,
+                        AstNodeType::AtomBaseNumberValue,
+                        AstNodeType::AtomIntFormat,
+                    ],
+                })
+            }
+        }
+    }
+
+    let base = base.ok_or_else(|| Error::MissingContent {
+        node_type: AstNodeType::AtomBaseNumberBase,
+        span: node.span().clone(),
+    })?;
+    let digits = digits.ok_or_else(|| Error::MissingContent {
+        node_type: AstNodeType::AtomBaseNumberValue,
+        span: node.span().clone(),
+    })?;
+
+    let value = BigInt::parse_bytes(digits.as_bytes(), base).ok_or_else(|| {
+        Error::MalformedNodeValue {
+            message: format!("can't parse number {}", digits),
+            span: node.span().clone(),
+        }
+    })?;
+
+    Ok(BaseNumber {
+        base,
+        digits,
+        value,
+        format,
+    })
+}
+
+fn parse_atom_base_num_into(node: &AstNode, buf: &mut Vec<CstAtom>) -> Result<(), Error> {
+    let number = parse_base_number(node)?;
+
+    let malformed = |message: String| Error::MalformedNodeValue {
+        message,
+        span: node.span().clone(),
+    };
+
+    match number.format {
+        Some(format) => {
+            let bytes = i128::try_from(&number.value)
+                .ok()
+                .and_then(|value| format.encode(value))
+                .ok_or_else(|| {
+                    malformed(format!("number {} doesn't fit {}", number.digits, format))
+                })?;
+            buf.push(CstAtom::Integer { bytes, format });
+        }
+        None => {
+            let value = u32::try_from(&number.value).map_err(|_| {
+                malformed(format!(
+                    "number {} requires integer format, like {}x{}:i64",
+                    number.digits, number.base, number.digits
+                ))
+            })?;
+            buf.push(CstAtom::Number(value));
+        }
+    }
 
     Ok(())
 }
 
+fn parse_atom_expression_into(node: &AstNode, buf: &mut Vec<CstAtom>) -> Result<(), Error> {
+    guard_node_type(node, AstNodeType::AtomExpr)?;
+    let mut expression = None;
+    let mut format = None;
+
+    for child in node.children() {
+        match child.node_type() {
+            AstNodeType::Expr => {
+                guard_empty(expression.as_ref(), child)?;
+                expression = Some(parse_expression(child)?);
+            }
+            AstNodeType::AtomIntFormat => {
+                guard_empty(format, child)?;
+                format = Some(parse_int_format(child)?);
+            }
+            _ => {
+                return Err(Error::UnexpectedNode {
+                    actual: child.node_type(),
+                    span: child.span().clone(),
+                    expected: vec![AstNodeType::Expr, AstNodeType::AtomIntFormat],
+                })
+            }
+        }
+    }
+
+    buf.push(CstAtom::Expression {
+        expression: Box::new(expression.ok_or_else(|| Error::MissingContent {
+            node_type: AstNodeType::Expr,
+            span: node.span().clone(),
+        })?),
+        format,
+        span: node.span().clone(),
+    });
+
+    Ok(())
+}
+
+/// Parses flat `operand (operator operand)*` sequence into a tree, respecting operator precedence
+fn parse_expression(node: &AstNode) -> Result<CstExpression, Error> {
+    fn reduce(operands: &mut Vec<CstExpression>, operator: CstOperator) {
+        let right = operands.pop().expect("operator must have right operand");
+        let left = operands.pop().expect("operator must have left operand");
+        operands.push(CstExpression::Binary {
+            operator,
+            span: left.span().join(right.span()),
+            left: Box::new(left),
+            right: Box::new(right),
+        });
+    }
+
+    guard_node_type(node, AstNodeType::Expr)?;
+    let mut operands = Vec::new();
+    let mut operators: Vec<CstOperator> = Vec::new();
+
+    for child in node.children() {
+        if child.node_type() != AstNodeType::ExprOperator {
+            operands.push(parse_expression_operand(child)?);
+            continue;
+        }
+
+        let symbol = parse_value_of(child)?;
+        let operator =
+            CstOperator::parse(symbol.as_str()).ok_or_else(|| Error::MalformedNodeValue {
+                message: format!("unknown operator {}", symbol),
+                span: child.span().clone(),
+            })?;
+
+        while let Some(top) = operators.last() {
+            if top.precedence() < operator.precedence() {
+                break;
+            }
+            let top = *top;
+            operators.pop();
+            reduce(&mut operands, top);
+        }
+        operators.push(operator);
+    }
+
+    if operands.len() != operators.len() + 1 {
+        return Err(Error::MalformedNodeValue {
+            message: "expression operator is missing an operand".to_string(),
+            span: node.span().clone(),
+        });
+    }
+
+    while let Some(operator) = operators.pop() {
+        reduce(&mut operands, operator);
+    }
+
+    Ok(operands.pop().expect("expression must have an operand"))
+}
+
+fn parse_expression_operand(node: &AstNode) -> Result<CstExpression, Error> {
+    let span = node.span().clone();
+
+    match node.node_type() {
+        AstNodeType::Expr => parse_expression(node),
+        AstNodeType::AtomBaseNumber => {
+            let number = parse_base_number(node)?;
+            if number.format.is_some() {
+                return Err(Error::MalformedNodeValue {
+                    message: "integer format can only be applied to the whole expression"
+                        .to_string(),
+                    span,
+                });
+            }
+            Ok(CstExpression::Number {
+                value: number.value,
+                span,
+            })
+        }
+        AstNodeType::AtomHex => {
+            let content = parse_value_of(node)?;
+            let value = BigInt::parse_bytes(content.as_bytes(), 16).ok_or_else(|| {
+                Error::MalformedNodeValue {
+                    message: format!("can't parse number {}", content),
+                    span: span.clone(),
+                }
+            })?;
+            Ok(CstExpression::Number { value, span })
+        }
+        AstNodeType::ExprLabel => {
+            match_ast!(
+                node => ExprLabel,
+                AtomLabelName => name | Ok
+            );
+            Ok(CstExpression::Label { name, span })
+        }
+        AstNodeType::AtomConst | AstNodeType::AtomFn => {
+            let mut atoms = Vec::new();
+            parse_atom_into(node, &mut atoms)?;
+            let atom = atoms.pop().expect("atom must be parsed");
+            Ok(CstExpression::Atom { atom, span })
+        }
+        _ => Err(Error::UnexpectedNode {
+            actual: node.node_type(),
+            span,
+            expected: vec![
+                AstNodeType::Expr,
+                AstNodeType::AtomBaseNumber,
+                AstNodeType::AtomHex,
+                AstNodeType::ExprLabel,
+                AstNodeType::AtomConst,
+                AstNodeType::AtomFn,
+            ],
+        }),
+    }
+}
+
 fn parse_value_of(node: &AstNode) -> Result<String, Error> {
     if !node.children().is_empty() {
         return Err(Error::UnexpectedChildren {
             node_type: node.node_type(),
+            span: node.span().clone(),
             children: node.children().iter().map(|x| x.node_type()).collect(),
         });
     }
 
     node.content()
-        .ok_or(Error::MissingContent {
+        .ok_or_else(|| Error::MissingContent {
             node_type: node.node_type(),
+            span: node.span().clone(),
         })
         .cloned()
 }
@@ -321,15 +1156,18 @@ fn guard_node_type(node: &AstNode, expected_type: AstNodeType) -> Result<(), Err
         return Err(Error::UnexpectedNode {
             actual: node.node_type(),
             expected: vec![expected_type],
+            span: node.span().clone(),
         });
     }
 
     Ok(())
 }
 
-fn guard_empty<T>(option: Option<T>) -> Result<(), Error> {
+fn guard_empty<T>(option: Option<T>, node: &AstNode) -> Result<(), Error> {
     if option.is_some() {
-        return Err(Error::DuplicateNode);
+        return Err(Error::DuplicateNode {
+            span: node.span().clone(),
+        });
     }
 
     Ok(())
@@ -1,9 +1,29 @@
 #[derive(Debug)]
-pub(crate) enum Error {
+pub enum Error {
     Io(std::io::Error),
     Ast(crate::compiler::ast::Error),
     Cst(crate::compiler::cst::Error),
     Rst(crate::compiler::rst::Error),
+    Multiple(Vec<Error>),
+}
+
+impl Error {
+    /// Wraps [errors] into [Error::Multiple] unless there is only one of them
+    pub(crate) fn from_many(mut errors: Vec<Error>) -> Error {
+        if errors.len() == 1 {
+            return errors.remove(0);
+        }
+
+        Error::Multiple(errors)
+    }
+
+    /// Number of individual errors, [Error::Multiple] is counted by its content
+    pub fn count(&self) -> usize {
+        match self {
+            Error::Multiple(errors) => errors.iter().map(Error::count).sum(),
+            _ => 1,
+        }
+    }
 }
 
 impl std::fmt::Display for Error {
@@ -11,8 +31,15 @@ impl std::fmt::Display for Error {
         match self {
             Error::Io(e) => write!(f, "IO error: {}", e),
             Error::Ast(e) => write!(f, "AST error: {}", e),
-            Error::Cst(e) => write!(f, "CST error: {}", e),
-            Error::Rst(e) => write!(f, "RST error: {}", e),
+            Error::Cst(e) => write!(f, "CST error: {}\n{}", e, e.span().snippet()),
+            Error::Rst(e) => match e.span() {
+                Some(span) => write!(f, "RST error: {}\n{}", e, span.snippet()),
+                None => write!(f, "RST error: {}", e),
+            },
+            Error::Multiple(errors) => {
+                let rendered: Vec<String> = errors.iter().map(Error::to_string).collect();
+                write!(f, "{}", rendered.join("\n\n"))
+            }
         }
     }
 }
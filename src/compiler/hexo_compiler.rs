@@ -1,49 +1,112 @@
 use crate::compiler::ast::{AstNode, AstParser};
-use crate::compiler::cst::{CstFile, CstParser};
+use crate::compiler::cst::{CstConstantStatement, CstFile, CstParser};
 use crate::compiler::error::Error;
-use crate::compiler::rst::{HexoFile, RstCompiler};
+use crate::compiler::rst::{HexoFile, RstCompiler, Warning};
 use crate::compiler::{Compilation, CompilerSource, HexoCompilerContext};
+use std::cell::RefCell;
+use std::path::Path;
 
-pub(crate) struct HexoCompiler {
+/// Path reported in errors of definitions, they don't come from any file
+const DEFINITION_PATH: &str = "--define";
+
+pub struct HexoCompiler {
     context: HexoCompilerContext,
+    warnings: RefCell<Vec<Warning>>,
 }
 
 impl HexoCompiler {
-    pub(crate) fn new(context: HexoCompilerContext) -> Self {
-        HexoCompiler { context }
+    pub fn new(context: HexoCompilerContext) -> Self {
+        HexoCompiler {
+            context,
+            warnings: RefCell::new(Vec::new()),
+        }
+    }
+
+    pub(crate) fn context(&self) -> &HexoCompilerContext {
+        &self.context
+    }
+
+    /// Warnings of all compilations done so far, including the failed ones, they are also logged
+    pub fn warnings(&self) -> Vec<Warning> {
+        self.warnings.borrow().clone()
+    }
+
+    pub(crate) fn add_warnings(&self, warnings: Vec<Warning>) {
+        self.warnings.borrow_mut().extend(warnings);
     }
 
-    pub(crate) fn compile_ast<TSource: CompilerSource>(
+    pub(crate) fn compile_ast<TSource: CompilerSource + ?Sized>(
         &self,
         source: &TSource,
     ) -> Result<AstNode, Error> {
         let ast_parser = AstParser::default();
         let source_text = source.read().map_err(Error::Io)?;
 
-        ast_parser.parse(source_text.as_str()).map_err(Error::Ast)
+        ast_parser
+            .parse(source.path(), source_text.as_str())
+            .map_err(Error::Ast)
     }
 
-    pub(crate) fn compile_cst<TSource: CompilerSource>(
+    pub(crate) fn compile_cst<TSource: CompilerSource + ?Sized>(
         &self,
         source: &TSource,
     ) -> Result<CstFile, Error> {
         let ast = self.compile_ast(source)?;
         let cst_parser = CstParser::default();
 
-        cst_parser.parse(source.path(), ast).map_err(Error::Cst)
+        cst_parser
+            .parse(source.path(), ast)
+            .map_err(|errors| Error::from_many(errors.into_iter().map(Error::Cst).collect()))
+    }
+
+    /// Parses `name=value` definitions of the context, all malformed ones are reported
+    pub(crate) fn compile_definitions(&self) -> Result<Vec<CstConstantStatement>, Error> {
+        let ast_parser = AstParser::default();
+        let cst_parser = CstParser::default();
+        let mut definitions = Vec::new();
+        let mut errors = Vec::new();
+
+        for definition in self.context.definitions() {
+            let result = ast_parser
+                .parse_definition(Path::new(DEFINITION_PATH), definition)
+                .map_err(Error::Ast)
+                .and_then(|ast| cst_parser.parse_definition(ast).map_err(Error::Cst));
+
+            match result {
+                Ok(statement) => definitions.push(statement),
+                Err(error) => errors.push(error),
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(Error::from_many(errors));
+        }
+
+        Ok(definitions)
     }
 
     pub(crate) fn compile_rst<TSource: CompilerSource>(
         &self,
         source: &TSource,
+    ) -> Result<HexoFile, Error> {
+        let definitions = self.compile_definitions()?;
+        self.compile_rst_with(source, definitions)
+    }
+
+    fn compile_rst_with<TSource: CompilerSource>(
+        &self,
+        source: &TSource,
+        definitions: Vec<CstConstantStatement>,
     ) -> Result<HexoFile, Error> {
         let cst = self.compile_cst(source)?;
-        let rst_compiler = RstCompiler::new(self, self.context.safe_mode());
+        let rst_compiler = RstCompiler::new(self, &self.context, source, definitions);
 
-        rst_compiler.compile(&cst).map_err(Error::Rst)
+        rst_compiler
+            .compile(&cst)
+            .map_err(|errors| Error::from_many(errors.into_iter().map(Error::Rst).collect()))
     }
 
-    pub(crate) fn compile<TSource: CompilerSource>(
+    pub fn compile<TSource: CompilerSource>(
         &self,
         source: &TSource,
     ) -> Result<Compilation, Error> {
@@ -51,6 +114,17 @@ impl HexoCompiler {
 
         Ok(Compilation::from(rst.emits().to_vec()))
     }
+
+    /// Compiles [source] evaluated by a file being compiled, like `#eval` argument, definitions
+    /// only apply to the compiled file
+    pub(crate) fn compile_nested<TSource: CompilerSource>(
+        &self,
+        source: &TSource,
+    ) -> Result<Compilation, Error> {
+        let rst = self.compile_rst_with(source, Vec::new())?;
+
+        Ok(Compilation::from(rst.emits().to_vec()))
+    }
 }
 
 #[cfg(test)]
@@ -70,3 +144,635 @@ mod benchmarks {
         b.iter(|| black_box(compiler.compile(&source).unwrap()));
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::compiler::compiler_source::LiteralCompilerSource;
+    use crate::compiler::{
+        FileCompilerSource, NativeFunction, NativeFunctionParameter, NativeFunctionSignature,
+        NativeParameterKind,
+    };
+    use crate::util::byte_buffer::ByteBuffer;
+    use std::collections::HashMap;
+    use std::path::{Path, PathBuf};
+    use std::rc::Rc;
+
+    #[test]
+    fn unresolved_constant_location() {
+        let compiler = HexoCompiler::new(HexoCompilerContext::new(false));
+        let source = LiteralCompilerSource::anonymous("> 00\n> 01 $hdr".to_string());
+
+        let error = compiler.compile(&source).err().unwrap();
+        let Error::Rst(crate::compiler::rst::Error::UnresolvedConstant { name, span }) = error else {
+            panic!("unexpected error {error}");
+        };
+
+        assert_eq!(name, "hdr");
+        assert_eq!((span.line(), span.column()), (2, 7));
+    }
+
+    #[test]
+    fn errors_are_collected() {
+        let compiler = HexoCompiler::new(HexoCompilerContext::new(false));
+        let source = LiteralCompilerSource::anonymous(
+            "$ a 01 $missing\n> $a $a\n> #unknown($x)\n> 10xzz".to_string(),
+        );
+
+        let error = compiler.compile(&source).err().unwrap();
+
+        // CST errors stop compilation before RST
+        assert_eq!(error.count(), 1);
+
+        let source = LiteralCompilerSource::anonymous(
+            "$ a 01 $missing\n> $a $a\n> #unknown($x)".to_string(),
+        );
+
+        let error = compiler.compile(&source).err().unwrap();
+
+        // poisoned $a is not reported again
+        assert_eq!(error.count(), 3);
+    }
+
+    #[test]
+    fn division_by_zero_location() {
+        let compiler = HexoCompiler::new(HexoCompilerContext::new(false));
+        let source = LiteralCompilerSource::anonymous("> (10x1 + 2 / (3 - 3)):u8".to_string());
+
+        let error = compiler.compile(&source).err().unwrap();
+        let Error::Rst(crate::compiler::rst::Error::DivisionByZero { span }) = error else {
+            panic!("unexpected error {error}");
+        };
+
+        assert_eq!((span.line(), span.column()), (1, 11));
+    }
+
+    #[test]
+    fn string_escapes() {
+        let compiler = HexoCompiler::new(HexoCompilerContext::new(false));
+        let source = LiteralCompilerSource::anonymous(
+            r"> ' a\t\0\x41\u{e9}\\\'''' r'\n''' ' '".to_string(),
+        );
+
+        let compilation = compiler.compile(&source).unwrap();
+
+        assert_eq!(compilation.content, b" a\t\0A\xc3\xa9\\''\\n'' ");
+    }
+
+    #[test]
+    fn invalid_escape_location() {
+        let compiler = HexoCompiler::new(HexoCompilerContext::new(false));
+        let source = LiteralCompilerSource::anonymous(r"> 'ok' 'a\qb'".to_string());
+
+        let error = compiler.compile(&source).err().unwrap();
+        let Error::Cst(crate::compiler::cst::Error::InvalidEscape { escape, span, .. }) = error
+        else {
+            panic!("unexpected error {error}");
+        };
+
+        assert_eq!(escape, r"\q");
+        assert_eq!((span.line(), span.column()), (1, 10));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn cmd_arguments_and_stdin() {
+        let compiler = HexoCompiler::new(HexoCompilerContext::new(false));
+        let source = LiteralCompilerSource::anonymous(
+            "> #cmd('printf', '%s-%s', 'a b', 'c') #cmd('cat', stdin: 01 02 0a, trim: 01)"
+                .to_string(),
+        );
+
+        let compilation = compiler.compile(&source).unwrap();
+
+        assert_eq!(compilation.content, b"a b-c\x01\x02");
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn cmd_failure_is_reported() {
+        let compiler = HexoCompiler::new(HexoCompilerContext::new(false));
+        let source = LiteralCompilerSource::anonymous(
+            "> #cmd('sh', '-c', 'echo oops >&2; exit 3')".to_string(),
+        );
+
+        let error = compiler.compile(&source).err().unwrap();
+
+        assert!(
+            error.to_string().contains("exit status: 3\noops"),
+            "{error}"
+        );
+    }
+
+    #[test]
+    fn constants_are_evaluated_per_call() {
+        let compiler = HexoCompiler::new(HexoCompilerContext::new(false));
+        let source = LiteralCompilerSource::anonymous(
+            "# twice {\n$ value $0 $0\n> $value\n}\n> #twice(01) #twice(02 03)".to_string(),
+        );
+
+        let compilation = compiler.compile(&source).unwrap();
+
+        assert_eq!(compilation.content, vec![0x01, 0x01, 0x02, 0x03, 0x02, 0x03]);
+    }
+
+    #[test]
+    fn function_argument_errors() {
+        let compiler = HexoCompiler::new(HexoCompilerContext::new(false));
+        let source = LiteralCompilerSource::anonymous(
+            "# f(a, b: 00) {\n> $a $b\n}\n> #f()\n> #f(01, c: 02)\n> #f(01, a: 02)".to_string(),
+        );
+
+        let error = compiler.compile(&source).err().unwrap();
+
+        assert_eq!(error.count(), 3);
+        let message = error.to_string();
+        assert!(message.contains("Missing argument a for function f"), "{message}");
+        assert!(message.contains("Unknown argument c for function f"), "{message}");
+    }
+
+    #[test]
+    fn native_function_argument_errors() {
+        let compiler = HexoCompiler::new(HexoCompilerContext::new(false));
+        let source = LiteralCompilerSource::anonymous(
+            "> #pad_left(01)\n> #pad(01, middle: 02)\n> #len(01, value: 02)".to_string(),
+        );
+
+        let error = compiler.compile(&source).err().unwrap();
+
+        assert_eq!(error.count(), 3);
+        let message = error.to_string();
+        assert!(
+            message.contains(
+                "Missing argument size for function pad_left, expected pad_left(value: bytes, size: integer)"
+            ),
+            "{message}"
+        );
+        assert!(message.contains("Unknown argument middle for function pad"), "{message}");
+        assert!(message.contains("Argument value for function len"), "{message}");
+    }
+
+    #[test]
+    fn output_range_errors() {
+        let compiler = HexoCompiler::new(HexoCompilerContext::new(false));
+        let source = LiteralCompilerSource::anonymous(
+            "@ start\n> #crc32(from: @start, to: @end)\n@ end\n> #crc8(from: @start, to: (@end + 10x9))"
+                .to_string(),
+        );
+
+        let error = compiler.compile(&source).err().unwrap();
+
+        assert_eq!(error.count(), 2);
+        let message = error.to_string();
+        assert!(
+            message.contains("Output range of function crc32 contains its own result"),
+            "{message}"
+        );
+        assert!(
+            message.contains("Output range 0..13 is out of bounds of 5 bytes long output"),
+            "{message}"
+        );
+    }
+
+    #[test]
+    fn declarations_follow_source_order() {
+        let compiler = HexoCompiler::new(HexoCompilerContext::new(false));
+        let cases: [(&str, &[u8]); 3] = [
+            ("#if (01) {\n$ a 01\n}\n$ b $a 02\n> $b", &[0x01, 0x02]),
+            ("#if (defined $x) {\n> 01\n} else {\n> 02\n}\n$ x 05", &[0x02]),
+            ("$ x 05\n#if (defined $x) {\n> 01\n} else {\n> 02\n}", &[0x01]),
+        ];
+
+        for (text, expected) in cases {
+            let source = LiteralCompilerSource::anonymous(text.to_string());
+            let compilation = compiler.compile(&source).unwrap();
+
+            assert_eq!(compilation.content, expected.to_vec(), "{text}");
+        }
+    }
+
+    #[test]
+    fn untaken_branches_are_checked() {
+        let compiler = HexoCompiler::new(HexoCompilerContext::new(false));
+        let source = LiteralCompilerSource::anonymous(
+            "#if (defined $debug) {\n> $missing\n} else {\n> 01\n}".to_string(),
+        );
+
+        // untaken branch is not evaluated
+        assert_eq!(compiler.compile(&source).unwrap().content, vec![0x01]);
+
+        let source =
+            LiteralCompilerSource::anonymous("#if (defined $debug) {\n> 'a\\qb'\n}".to_string());
+
+        let error = compiler.compile(&source).err().unwrap();
+        let Error::Cst(crate::compiler::cst::Error::InvalidEscape { .. }) = error else {
+            panic!("unexpected error {error}");
+        };
+
+        let source = LiteralCompilerSource::anonymous("#if (@end) {\n> 01\n}\n@end".to_string());
+
+        let error = compiler.compile(&source).err().unwrap();
+        let Error::Rst(crate::compiler::rst::Error::LabelInCondition { span }) = error else {
+            panic!("unexpected error {error}");
+        };
+        assert_eq!((span.line(), span.column()), (1, 5));
+    }
+
+    #[test]
+    fn output_offset_in_condition() {
+        let compiler = HexoCompiler::new(HexoCompilerContext::new(false));
+        let source = LiteralCompilerSource::anonymous(
+            "> 01 02 03\n#if (#offset() == 03) {\n> aa\n} else {\n> bb\n}".to_string(),
+        );
+
+        let error = compiler.compile(&source).err().unwrap();
+        let Error::Rst(crate::compiler::rst::Error::OutputOffsetInCondition { name, span }) = error
+        else {
+            panic!("unexpected error {error}");
+        };
+        assert_eq!(name, "offset");
+        assert_eq!(span.line(), 2);
+
+        // assertions see the output built so far, arguments count offsets from their own start
+        let source = LiteralCompilerSource::anonymous(
+            "> 01 02 03\n#assert (#offset() == 03)\n#if (#len(#align(04) ff) == 01) {\n> aa\n}"
+                .to_string(),
+        );
+        assert_eq!(
+            compiler.compile(&source).unwrap().content,
+            vec![0x01, 0x02, 0x03, 0xAA]
+        );
+    }
+
+    #[test]
+    fn alignment_follows_output_offset() {
+        let compiler = HexoCompiler::new(HexoCompilerContext::new(false));
+        let source = LiteralCompilerSource::anonymous(
+            "$ block AA #align(04)\n# sector {\n> #align(04, pattern: FF)\n}\n> 01 #sector() $block"
+                .to_string(),
+        );
+
+        let compilation = compiler.compile(&source).unwrap();
+
+        // inside of a constant alignment is relative to the constant start
+        assert_eq!(
+            compilation.content,
+            vec![0x01, 0xFF, 0xFF, 0xFF, 0xAA, 0x00, 0x00, 0x00]
+        );
+
+        let source = LiteralCompilerSource::anonymous("> 01 02 03 #fill_to(02)".to_string());
+        let message = compiler.compile(&source).err().unwrap().to_string();
+        assert!(
+            message.contains("output is already 3 bytes long"),
+            "{message}"
+        );
+    }
+
+    #[test]
+    fn signed_encodings_of_untyped_values() {
+        let compiler = HexoCompiler::new(HexoCompilerContext::new(false));
+        let cases: [(&str, &[u8]); 5] = [
+            ("> #sleb128(10x200)", &[0xC8, 0x01]),
+            ("> #varint_zigzag(10x200)", &[0x90, 0x03]),
+            ("> #sleb128(10x40000)", &[0xC0, 0xB8, 0x02]),
+            ("> #sleb128(10x-2:i8 00)", &[0x80, 0xFC, 0x03]),
+            ("$ value 10x-2:i8\n> #sleb128($value)", &[0x7E]),
+        ];
+
+        for (text, expected) in cases {
+            let source = LiteralCompilerSource::anonymous(text.to_string());
+            let compilation = compiler.compile(&source).unwrap();
+
+            assert_eq!(compilation.content, expected.to_vec(), "{text}");
+        }
+    }
+
+    #[test]
+    fn registered_native_function() {
+        let symbols = HashMap::from([
+            ("main".to_string(), 0x1000u32),
+            ("exit".to_string(), 0x2000),
+        ]);
+        let mut context = HexoCompilerContext::new(true);
+        context
+            .register_native_function(NativeFunction::new(
+                NativeFunctionSignature::new("symbol").with_param(
+                    NativeFunctionParameter::positional("name", NativeParameterKind::String),
+                ),
+                move |arguments, _| {
+                    let name = arguments.string("name")?;
+                    let address = symbols.get(&name).ok_or_else(|| {
+                        arguments.invalid("name", format!("unknown symbol {name}"))
+                    })?;
+                    Ok(ByteBuffer::from(address.to_le_bytes().to_vec()))
+                },
+            ))
+            .unwrap();
+
+        let compiler = HexoCompiler::new(context);
+        let source = LiteralCompilerSource::anonymous("> #symbol('exit') #len(01 02)".to_string());
+
+        let compilation = compiler.compile(&source).unwrap();
+
+        assert_eq!(compilation.content, vec![0x00, 0x20, 0x00, 0x00, 0x02]);
+    }
+
+    #[test]
+    fn native_function_name_collision() {
+        let mut context = HexoCompilerContext::new(false);
+        let function = NativeFunction::new(NativeFunctionSignature::new("len"), |_, _| {
+            Ok(ByteBuffer::default())
+        });
+
+        let error = context.register_native_function(function).err().unwrap();
+
+        assert_eq!(error.to_string(), "Native function len is already defined");
+    }
+
+    #[test]
+    fn custom_safe_functions() {
+        let context = HexoCompilerContext::new(true).with_safe_functions(&["len", "eval"]);
+        let compiler = HexoCompiler::new(context);
+
+        let source = LiteralCompilerSource::anonymous("> #eval('> #len(01)')".to_string());
+        assert_eq!(compiler.compile(&source).unwrap().content, vec![0x01]);
+
+        let source = LiteralCompilerSource::anonymous("> #pad_left(01, 02)".to_string());
+        let error = compiler.compile(&source).err().unwrap();
+        let Error::Rst(crate::compiler::rst::Error::NativeFunctionIsUnsafe { name, .. }) = error
+        else {
+            panic!("unexpected error {error}");
+        };
+        assert_eq!(name, "pad_left");
+    }
+
+    #[test]
+    fn recursion_depth_limit() {
+        let compiler = HexoCompiler::new(HexoCompilerContext::new(false));
+        let source = LiteralCompilerSource::anonymous(
+            "# forever {\n> #forever()\n}\n> #forever()".to_string(),
+        );
+
+        let error = compiler.compile(&source).err().unwrap();
+        let Error::Rst(crate::compiler::rst::Error::CallDepthExceeded {
+            limit, call_stack, ..
+        }) = error
+        else {
+            panic!("unexpected error {error}");
+        };
+
+        assert_eq!(limit, HexoCompilerContext::DEFAULT_MAX_CALL_DEPTH);
+        assert_eq!(call_stack.len(), limit + 1);
+    }
+
+    #[test]
+    fn loop_iteration_limit() {
+        let context = HexoCompilerContext::new(false).with_max_loop_iterations(4);
+        let compiler = HexoCompiler::new(context);
+
+        let source = LiteralCompilerSource::anonymous("#for $i in 00..04 {\n> $i\n}".to_string());
+        assert_eq!(
+            compiler.compile(&source).unwrap().content,
+            vec![0x00, 0x01, 0x02, 0x03]
+        );
+
+        let source = LiteralCompilerSource::anonymous(
+            "#for $i in 00..10x5 {\n> $i\n}\n#for $b in 'hexo' {\n> $missing\n}".to_string(),
+        );
+        let error = compiler.compile(&source).err().unwrap();
+
+        // failing body is reported once, not on every iteration
+        assert_eq!(error.count(), 2);
+        let message = error.to_string();
+        assert!(
+            message.contains("Loop would run 5 iterations, more than the limit of 4"),
+            "{message}"
+        );
+    }
+
+    #[test]
+    fn output_size_limit() {
+        let compiler = HexoCompiler::new(HexoCompilerContext::new(false));
+        let source = LiteralCompilerSource::anonymous(
+            "> #repeat(0102, 16xFFFFFFFFFFFFFFFF:u64)\n> #fill(16xFFFFFFFFFFF:u64)".to_string(),
+        );
+
+        let error = compiler.compile(&source).err().unwrap();
+
+        assert_eq!(error.count(), 2);
+        let message = error.to_string();
+        assert!(
+            message.contains("Output of function repeat exceeds limit of 268435456 bytes"),
+            "{message}"
+        );
+        assert!(
+            message.contains("Output of function fill exceeds limit of 268435456 bytes"),
+            "{message}"
+        );
+
+        let context = HexoCompilerContext::new(false).with_max_output_size(4);
+        let compiler = HexoCompiler::new(context);
+
+        let source = LiteralCompilerSource::anonymous("> #repeat(0102, 02) #fill(04)".to_string());
+        assert_eq!(
+            compiler.compile(&source).unwrap().content,
+            vec![0x01, 0x02, 0x01, 0x02, 0x00, 0x00, 0x00, 0x00]
+        );
+
+        let source = LiteralCompilerSource::anonymous(
+            "> #repeat(0102, 03)\n> #pad_left(01, 16xFFFFFFFFFF:u64)\n> #utf32le('ab')".to_string(),
+        );
+        let error = compiler.compile(&source).err().unwrap();
+
+        // every native function is limited, not only the ones building output from their arguments
+        assert_eq!(error.count(), 3);
+        let message = error.to_string();
+        for function in ["repeat", "pad_left", "utf32le"] {
+            assert!(
+                message.contains(&format!(
+                    "Output of function {function} exceeds limit of 4 bytes"
+                )),
+                "{message}"
+            );
+        }
+    }
+
+    #[test]
+    fn terminated_and_prefixed_output_size_limit() {
+        let compiler = HexoCompiler::new(HexoCompilerContext::new(false));
+        let source = LiteralCompilerSource::anonymous(
+            "> #null_terminated(01, width: 16xFFFFFFFF:u32)\n\
+            > #null_terminated(01, width: 16xFFFFFFFFFFFFFFFF:u64)"
+                .to_string(),
+        );
+
+        let error = compiler.compile(&source).err().unwrap();
+        assert_eq!(error.count(), 2);
+        let message = error.to_string();
+        assert!(
+            message.contains("Output of function null_terminated exceeds limit"),
+            "{message}"
+        );
+
+        let context = HexoCompilerContext::new(false).with_max_output_size(4);
+        let compiler = HexoCompiler::new(context);
+
+        let source = LiteralCompilerSource::anonymous(
+            "> #len_prefixed(010203) #null_terminated(01, width: 03)".to_string(),
+        );
+        assert_eq!(
+            compiler.compile(&source).unwrap().content,
+            vec![0x03, 0x01, 0x02, 0x03, 0x01, 0x00, 0x00, 0x00]
+        );
+
+        let source = LiteralCompilerSource::anonymous("> #len_prefixed(01020304)".to_string());
+        let message = compiler.compile(&source).err().unwrap().to_string();
+        assert!(
+            message.contains("Output of function len_prefixed exceeds limit of 4 bytes"),
+            "{message}"
+        );
+    }
+
+    #[test]
+    fn definitions_override_constants() {
+        let context = HexoCompilerContext::new(false).with_definitions(vec![
+            "version=02".to_string(),
+            "debug = #repeat(ff, 02)".to_string(),
+        ]);
+        let compiler = HexoCompiler::new(context);
+        let source = LiteralCompilerSource::anonymous(
+            "$? version $missing\n#if (defined $debug) {\n> $debug\n}\n# f {\n$ version 03\n> $version\n}\n> $version #f()"
+                .to_string(),
+        );
+
+        // overridden value is never evaluated, constants of functions are left as they are
+        let compilation = compiler.compile(&source).unwrap();
+        assert_eq!(compilation.content, vec![0xff, 0xff, 0x02, 0x03]);
+
+        let context = HexoCompilerContext::new(false)
+            .with_definitions(vec!["a 01".to_string(), "b=$c".to_string()]);
+        let compiler = HexoCompiler::new(context);
+        let source = LiteralCompilerSource::anonymous("> 00".to_string());
+
+        let error = compiler.compile(&source).err().unwrap();
+        assert!(error.to_string().contains("--define:1:1"), "{error}");
+    }
+
+    #[test]
+    fn definitions_are_not_applied_to_eval() {
+        let context =
+            HexoCompilerContext::new(false).with_definitions(vec!["version=05".to_string()]);
+        let compiler = HexoCompiler::new(context);
+
+        let source =
+            LiteralCompilerSource::anonymous("$? version 01\n> $version #eval('> 02')".to_string());
+        assert_eq!(compiler.compile(&source).unwrap().content, vec![0x05, 0x02]);
+        assert!(compiler.warnings().is_empty(), "{:?}", compiler.warnings());
+
+        let source =
+            LiteralCompilerSource::anonymous("$? version 01\n> #eval('> $version')".to_string());
+        let message = compiler.compile(&source).err().unwrap().to_string();
+        assert!(message.contains("Unresolved constant: version"), "{message}");
+    }
+
+    #[test]
+    fn output_range_bound_must_be_label_reference() {
+        let compiler = HexoCompiler::new(HexoCompilerContext::new(false));
+        let sources = [
+            "# f {\n@here\n}\n> #crc32(from: #f(), to: @b)\n> 01 02\n@b",
+            "@a\n> #crc32(from: #crc32(from: @a, to: @b), to: @b)\n> 01 02\n@b",
+        ];
+
+        for source in sources {
+            let source = LiteralCompilerSource::anonymous(source.to_string());
+
+            let error = compiler.compile(&source).err().unwrap();
+            let Error::Rst(crate::compiler::rst::Error::InvalidOutputRangeBound { name, .. }) =
+                error
+            else {
+                panic!("unexpected error {error}");
+            };
+
+            assert_eq!(name, "from");
+        }
+    }
+
+    #[test]
+    fn failed_assertions() {
+        let compiler = HexoCompiler::new(HexoCompilerContext::new(false));
+        let source = LiteralCompilerSource::anonymous(
+            "$ key 0102\n#assert (#len($key) == 10) 'key is ' #hex($key)\n> 'abc'\n#assert (#offset() < 03)\n#assert ($missing)"
+                .to_string(),
+        );
+
+        let error = compiler.compile(&source).err().unwrap();
+
+        assert_eq!(error.count(), 3);
+        let message = error.to_string();
+        assert!(message.contains("Assertion failed: key is 0102\n"), "{message}");
+        assert!(message.contains("Assertion failed\n"), "{message}");
+        assert!(message.contains("Unresolved constant: missing"), "{message}");
+    }
+
+    #[test]
+    fn include_cycle() {
+        let compiler = HexoCompiler::new(HexoCompilerContext::new(false));
+        let source = FileCompilerSource::new(Path::new("samples/include_cycle/input.hexo"));
+
+        let error = compiler.compile(&source).err().unwrap();
+        let Error::Rst(crate::compiler::rst::Error::IncludeCycle { chain, .. }) = error else {
+            panic!("unexpected error {error}");
+        };
+
+        assert_eq!(chain.len(), 3);
+        assert_eq!(chain.first(), chain.last());
+    }
+
+    /// In-memory files, included ones are looked up in the same map
+    struct MemorySource {
+        path: PathBuf,
+        files: Rc<HashMap<PathBuf, String>>,
+    }
+
+    impl CompilerSource for MemorySource {
+        fn read(&self) -> Result<String, std::io::Error> {
+            self.files
+                .get(&self.path)
+                .cloned()
+                .ok_or_else(|| std::io::Error::from(std::io::ErrorKind::NotFound))
+        }
+
+        fn path(&self) -> &Path {
+            &self.path
+        }
+
+        fn include(&self, path: &Path) -> Box<dyn CompilerSource> {
+            Box::new(MemorySource {
+                path: path.to_path_buf(),
+                files: self.files.clone(),
+            })
+        }
+    }
+
+    #[test]
+    fn includes_are_opened_by_source() {
+        let files = HashMap::from([
+            (
+                PathBuf::from("mem/main.hexo"),
+                "#include 'lib/a.hexo'\n> $a $b".to_string(),
+            ),
+            (
+                PathBuf::from("mem/lib/a.hexo"),
+                "#include 'b.hexo'\n$ a 01".to_string(),
+            ),
+            (PathBuf::from("mem/lib/b.hexo"), "$ b 02".to_string()),
+        ]);
+        let source = MemorySource {
+            path: PathBuf::from("mem/main.hexo"),
+            files: Rc::new(files),
+        };
+
+        let compiler = HexoCompiler::new(HexoCompilerContext::new(false));
+        assert_eq!(compiler.compile(&source).unwrap().content, vec![0x01, 0x02]);
+    }
+}
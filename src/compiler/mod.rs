@@ -7,9 +7,21 @@ mod error;
 mod hexo_compiler;
 mod native_fn;
 mod rst;
+mod span;
 
-pub(crate) use compilation_result::Compilation;
-pub(crate) use compiler_context::HexoCompilerContext;
-pub(crate) use compiler_source::{CompilerSource, FileCompilerSource};
-pub(crate) use error::Error;
-pub(crate) use hexo_compiler::HexoCompiler;
+pub use ast::Error as AstError;
+pub use compilation_result::Compilation;
+pub use compiler_context::HexoCompilerContext;
+pub use compiler_source::{CompilerSource, FileCompilerSource, LiteralCompilerSource};
+pub use cst::Error as CstError;
+pub use error::Error;
+pub use hexo_compiler::HexoCompiler;
+pub use native_fn::Error as NativeFunctionError;
+pub(crate) use native_fn::NativeFunctionIndex;
+pub use native_fn::{
+    NativeFunction, NativeFunctionArguments, NativeFunctionDefinition, NativeFunctionParameter,
+    NativeFunctionSignature, NativeParameterKind,
+};
+pub use rst::Error as RstError;
+pub use rst::Warning;
+pub use span::Span;
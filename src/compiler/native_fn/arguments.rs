@@ -1,36 +1,154 @@
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use num_bigint::{BigInt, Sign};
+
 use crate::compiler::native_fn::Error;
+use crate::compiler::native_fn::signature::NativeFunctionSignature;
 use crate::util::byte_buffer::ByteBuffer;
 
-pub(crate) struct NativeFunctionArguments<'a> {
-    args: &'a HashMap<String, ByteBuffer>,
+/// Arguments of a call checked against [NativeFunctionSignature], keyed by parameter name
+pub struct NativeFunctionArguments<'a> {
+    signature: &'a NativeFunctionSignature,
+    args: HashMap<String, ByteBuffer>,
+    variadic: Vec<ByteBuffer>,
+    /// Source file containing the function call
+    source_path: &'a Path,
 }
 
 impl NativeFunctionArguments<'_> {
-    pub(crate) fn new(args: &HashMap<String, ByteBuffer>) -> NativeFunctionArguments {
+    pub(crate) fn new<'a>(
+        signature: &'a NativeFunctionSignature,
+        args: HashMap<String, ByteBuffer>,
+        variadic: Vec<ByteBuffer>,
+        source_path: &'a Path,
+    ) -> NativeFunctionArguments<'a> {
         NativeFunctionArguments {
-            args
+            signature,
+            args,
+            variadic,
+            source_path,
         }
     }
 
-    pub(crate) fn get_argument_at(
-        &self,
-        pos: usize,
-        fn_name: &str,
-    ) -> Result<&ByteBuffer, Error> {
-        let arguments = &self.args;
-        arguments.get(&pos.to_string())
-            .ok_or_else(move || Error::MissingArgument {
-                name: pos.to_string(),
-                available_arguments: arguments.keys().cloned().collect(),
-                function_name: fn_name.to_string(),
-            })
+    /// Directory of the calling source file, [None] for sources that don't come from a file
+    pub fn source_directory(&self) -> Option<&Path> {
+        self.source_path
+            .parent()
+            .filter(|directory| !directory.as_os_str().is_empty() && self.source_path.is_file())
+    }
+
+    /// Resolves relative [path] against directory of the calling source file
+    pub fn resolve_path(&self, path: &str) -> PathBuf {
+        match self.source_directory() {
+            Some(directory) => directory.join(path),
+            None => PathBuf::from(path),
+        }
     }
 
-    pub(crate) fn get_named_argument(
-        &self,
-        name: &str,
-    ) -> Option<&ByteBuffer> {
+    pub fn get(&self, name: &str) -> Option<&ByteBuffer> {
         self.args.get(name)
     }
-}
\ No newline at end of file
+
+    pub fn bytes(&self, name: &str) -> Result<&ByteBuffer, Error> {
+        self.get(name).ok_or_else(|| Error::MissingArgument {
+            name: name.to_string(),
+            function_name: self.signature.name().to_string(),
+            signature: self.signature.to_string(),
+        })
+    }
+
+    pub fn usize(&self, name: &str) -> Result<usize, Error> {
+        self.bytes(name)
+            .and_then(|value| self.to_usize(name, value))
+    }
+
+    pub fn optional_usize(&self, name: &str) -> Result<Option<usize>, Error> {
+        self.get(name)
+            .map(|value| self.to_usize(name, value))
+            .transpose()
+    }
+
+    /// Unsigned integer of any size
+    pub(crate) fn big_integer(&self, name: &str) -> Result<BigInt, Error> {
+        self.bytes(name)
+            .map(|value| BigInt::from_bytes_be(Sign::Plus, &value.to_vec()))
+    }
+
+    /// Two's complement integer of any size if written in a signed format, like `10x-2:i8`,
+    /// otherwise the same as [Self::big_integer]
+    pub(crate) fn signed_big_integer(&self, name: &str) -> Result<BigInt, Error> {
+        let value = self.bytes(name)?;
+        if value.is_signed() {
+            Ok(BigInt::from_signed_bytes_be(&value.to_vec()))
+        } else {
+            self.big_integer(name)
+        }
+    }
+
+    pub fn string(&self, name: &str) -> Result<String, Error> {
+        self.bytes(name)
+            .and_then(|value| self.to_string(name, value))
+    }
+
+    pub fn optional_string(&self, name: &str) -> Result<Option<String>, Error> {
+        self.get(name)
+            .map(|value| self.to_string(name, value))
+            .transpose()
+    }
+
+    /// Path argument resolved against directory of the calling source file
+    pub fn path(&self, name: &str) -> Result<PathBuf, Error> {
+        self.string(name).map(|path| self.resolve_path(&path))
+    }
+
+    pub fn optional_path(&self, name: &str) -> Result<Option<PathBuf>, Error> {
+        self.optional_string(name)
+            .map(|path| path.map(|path| self.resolve_path(&path)))
+    }
+
+    /// Positional arguments collected by variadic parameter
+    pub fn variadic(&self) -> &Vec<ByteBuffer> {
+        &self.variadic
+    }
+
+    /// Named arguments starting with [prefix], returned without it and sorted by name
+    pub fn prefixed<'p>(&'p self, prefix: &'p str) -> Vec<(&'p str, &'p ByteBuffer)> {
+        let mut arguments: Vec<_> = self
+            .args
+            .iter()
+            .filter_map(|(name, value)| Some((name.strip_prefix(prefix)?, value)))
+            .collect();
+        arguments.sort_by_key(|(name, _)| *name);
+        arguments
+    }
+
+    /// Checks output [size] against [limit], [None] stands for a size overflowing [usize]
+    pub fn output_size(&self, size: Option<usize>, limit: usize) -> Result<usize, Error> {
+        size.filter(|size| *size <= limit)
+            .ok_or_else(|| Error::OutputSizeExceeded {
+                function_name: self.signature.name().to_string(),
+                limit,
+            })
+    }
+
+    pub fn invalid(&self, name: &str, message: String) -> Error {
+        Error::InvalidArgument {
+            name: name.to_string(),
+            function_name: self.signature.name().to_string(),
+            message,
+        }
+    }
+
+    fn to_usize(&self, name: &str, value: &ByteBuffer) -> Result<usize, Error> {
+        value
+            .to_usize()
+            .ok_or_else(|| self.invalid(name, "value is too large".to_string()))
+    }
+
+    fn to_string(&self, name: &str, value: &ByteBuffer) -> Result<String, Error> {
+        value
+            .to_string()
+            .map_err(|e| self.invalid(name, e.to_string()))
+    }
+}
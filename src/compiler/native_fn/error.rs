@@ -1,10 +1,41 @@
+use std::path::PathBuf;
+
 #[derive(Clone, Debug)]
-pub(crate) enum Error {
+pub enum Error {
     Unknown(String),
     MissingArgument {
         name: String,
-        available_arguments: Vec<String>,
         function_name: String,
+        signature: String,
+    },
+    UnknownArgument {
+        name: String,
+        function_name: String,
+        signature: String,
+    },
+    DuplicateArgument {
+        name: String,
+        function_name: String,
+    },
+    InvalidArgument {
+        name: String,
+        function_name: String,
+        message: String,
+    },
+    Io {
+        path: PathBuf,
+        message: String,
+    },
+    CommandFailed {
+        command: String,
+        message: String,
+    },
+    FunctionAlreadyDefined {
+        name: String,
+    },
+    OutputSizeExceeded {
+        function_name: String,
+        limit: usize,
     },
 }
 
@@ -14,13 +45,64 @@ impl std::fmt::Display for Error {
             Error::Unknown(name) => write!(f, "Unknown error: {}", name),
             Error::MissingArgument {
                 name,
-                available_arguments,
                 function_name,
+                signature,
+            } => {
+                write!(
+                    f,
+                    "Missing argument {} for function {}, expected {}",
+                    name, function_name, signature
+                )
+            }
+            Error::UnknownArgument {
+                name,
+                function_name,
+                signature,
+            } => {
+                write!(
+                    f,
+                    "Unknown argument {} for function {}, expected {}",
+                    name, function_name, signature
+                )
+            }
+            Error::DuplicateArgument {
+                name,
+                function_name,
+            } => {
+                write!(
+                    f,
+                    "Argument {} for function {} is passed both by position and by name",
+                    name, function_name
+                )
+            }
+            Error::InvalidArgument {
+                name,
+                function_name,
+                message,
+            } => {
+                write!(
+                    f,
+                    "Invalid argument {} for function {}: {}",
+                    name, function_name, message
+                )
+            }
+            Error::Io { path, message } => {
+                write!(f, "Can't read file {}: {}", path.display(), message)
+            }
+            Error::CommandFailed { command, message } => {
+                write!(f, "Command `{}` failed: {}", command, message)
+            }
+            Error::FunctionAlreadyDefined { name } => {
+                write!(f, "Native function {} is already defined", name)
+            }
+            Error::OutputSizeExceeded {
+                function_name,
+                limit,
             } => {
                 write!(
                     f,
-                    "Missing argument {} for function {}. Available arguments: {:?}",
-                    name, function_name, available_arguments
+                    "Output of function {} exceeds limit of {} bytes",
+                    function_name, limit
                 )
             }
         }
@@ -1,6 +1,7 @@
 use crate::compiler::compiler_source::LiteralCompilerSource;
 use crate::compiler::native_fn::{
-    Error, NativeFunction, NativeFunctionDefinition, NativeFunctionSignature,
+    Error, NativeFunction, NativeFunctionDefinition, NativeFunctionParameter,
+    NativeFunctionSignature, NativeParameterKind,
 };
 use crate::util::byte_buffer::ByteBuffer;
 
@@ -10,16 +11,16 @@ pub(crate) struct EvalNativeFunctionDef {}
 impl NativeFunctionDefinition for EvalNativeFunctionDef {
     fn create(&self) -> NativeFunction {
         NativeFunction::new(
-            NativeFunctionSignature::new_unsafe("eval"),
+            NativeFunctionSignature::new_unsafe("eval")
+                .with_doc("Compiles hexo source and emits its output")
+                .with_param(NativeFunctionParameter::positional(
+                    "source",
+                    NativeParameterKind::String,
+                )),
             |arguments, compiler| {
-                let buffer = arguments.get_argument_at(0, "eval")?.clone();
-                let source = LiteralCompilerSource::anonymous(
-                    buffer
-                        .to_string()
-                        .map_err(|e| Error::Unknown(e.to_string()))?,
-                );
+                let source = LiteralCompilerSource::anonymous(arguments.string("source")?);
                 let result = compiler
-                    .compile(&source)
+                    .compile_nested(&source)
                     .map_err(|e| Error::Unknown(e.to_string()))?;
                 Ok(ByteBuffer::from(result.content))
             },
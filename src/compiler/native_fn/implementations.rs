@@ -1,109 +1,245 @@
 use std::fs::File;
-use std::io::Read;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::process::{Command, Stdio};
 
+use num_bigint::BigInt;
+
+use crate::compiler::cst::decode_bytes_from_string;
 use crate::compiler::native_fn::error::Error;
+use crate::compiler::native_fn::parameter::NativeFunctionParameter as Param;
+use crate::compiler::native_fn::parameter::NativeParameterKind as Kind;
 use crate::compiler::native_fn::signature::{NativeFunction, NativeFunctionSignature};
+use crate::compiler::native_fn::NativeFunctionArguments;
+use crate::compiler::HexoCompiler;
 use crate::util::byte_buffer::ByteBuffer;
+use crate::util::checksum::{adler32, fletcher16, fletcher32, CrcAlgorithm};
+use crate::util::integer_format::IntegerFormat;
+use crate::util::text_codec::{pem_body, pem_encode, strip_hex_separators, BaseAlphabet};
+use crate::util::text_encoding::TextEncoding;
+use crate::util::varint::VarIntEncoding;
 
 pub(crate) fn create_len_native_function() -> NativeFunction {
     NativeFunction::new(
-        NativeFunctionSignature::new("len"),
+        NativeFunctionSignature::new("len")
+            .with_doc(
+                "Length of value in bytes, as few big endian bytes as possible \
+                or in given variable length encoding: uleb128, sleb128, varint, varint_zigzag, vlq",
+            )
+            .with_param(Param::positional("value", Kind::Bytes))
+            .with_param(Param::named("encoding", Kind::String).optional()),
         |arguments, _| {
-            let mut result = ByteBuffer::default();
-            let arg0 = arguments.get_named_argument("utf8")
-                .unwrap_or_else(|| arguments.get_argument_at(0, "len").unwrap());
+            let len = arguments.bytes("value")?.len();
+
+            let Some(encoding) = arguments.optional_string("encoding")? else {
+                let mut result = ByteBuffer::default();
+                result.push_u32_shrunk(len as u32);
+                return Ok(result);
+            };
+
+            let encoding = VarIntEncoding::parse(&encoding).ok_or_else(|| {
+                arguments.invalid("encoding", format!("unknown encoding {}", encoding))
+            })?;
+            let bytes = encoding
+                .encode(&BigInt::from(len))
+                .expect("length is not negative");
 
-            let len = arg0.len() as u32;
-            result.push_u32_shrunk(len);
-            Ok(result)
+            Ok(ByteBuffer::from(bytes))
         },
     )
 }
 
 pub(crate) fn create_pad_left_native_function() -> NativeFunction {
     NativeFunction::new(
-        NativeFunctionSignature::new("pad_left"),
-        |arguments, _| {
-            let mut arg0 = arguments.get_argument_at(0, "pad_left")?.clone();
-            let arg1 = arguments.get_argument_at(1, "pad_left")?;
-
-            arg0.pad_left(arg1.as_usize_unsafe());
+        NativeFunctionSignature::new("pad_left")
+            .with_doc("Pads value with zeros on the left up to size bytes")
+            .with_param(Param::positional("value", Kind::Bytes))
+            .with_param(Param::positional("size", Kind::Integer)),
+        |arguments, compiler| {
+            let mut value = arguments.bytes("value")?.clone();
+            let size = arguments.usize("size")?;
+            arguments.output_size(Some(size), compiler.context().max_output_size())?;
+            value.pad_left(size);
 
-            Ok(arg0.clone())
+            Ok(value)
         },
     )
 }
 
 pub(crate) fn create_pad_right_native_function() -> NativeFunction {
     NativeFunction::new(
-        NativeFunctionSignature::new("pad_right"),
-        |arguments, _| {
-            let mut arg0: ByteBuffer = arguments.get_argument_at(0, "pad_right")?.clone();
-            let arg1 = arguments.get_argument_at(1, "pad_right")?;
+        NativeFunctionSignature::new("pad_right")
+            .with_doc("Pads value with zeros on the right up to size bytes")
+            .with_param(Param::positional("value", Kind::Bytes))
+            .with_param(Param::positional("size", Kind::Integer)),
+        |arguments, compiler| {
+            let mut value = arguments.bytes("value")?.clone();
+            let size = arguments.usize("size")?;
+            arguments.output_size(Some(size), compiler.context().max_output_size())?;
+            value.pad_right(size);
 
-            arg0.pad_right(arg1.as_usize_unsafe());
-
-            Ok(arg0.clone())
+            Ok(value)
         },
     )
 }
 
 pub(crate) fn create_cmd_native_function() -> NativeFunction {
     NativeFunction::new(
-        NativeFunctionSignature::new_unsafe("cmd"),
+        NativeFunctionSignature::new_unsafe("cmd")
+            .with_doc(
+                "Output of the command, single command string is split on whitespace, \
+                following arguments are passed as is. Fails if the command exits with non-zero status",
+            )
+            .with_param(Param::positional("command", Kind::String))
+            .with_param(Param::variadic("args", Kind::String))
+            .with_param(Param::named("cwd", Kind::Path).optional())
+            .with_param(Param::named("stdin", Kind::Bytes).optional())
+            .with_param(Param::named("trim", Kind::Integer).with_default(vec![0]))
+            .with_param(Param::prefixed("env_", Kind::String)),
         |arguments, _| {
-            let command = arguments.get_argument_at(0, "cmd")?
-                .to_string()
-                .map_err(|e| Error::Unknown(e.to_string()))?;
+            let mut argv: Vec<String> = arguments
+                .string("command")?
+                .split_whitespace()
+                .map(String::from)
+                .collect();
+            for argument in arguments.variadic() {
+                let argument = argument
+                    .to_string()
+                    .map_err(|e| arguments.invalid("args", e.to_string()))?;
+                argv.push(argument);
+            }
 
-            let output = std::process::Command::new(command)
-                .output()
-                .map_err(|e| Error::Unknown(format!("Error executing command: {}", e)))?;
+            let Some((program, args)) = argv.split_first() else {
+                return Err(arguments.invalid("command", "command is empty".to_string()));
+            };
 
-            let buffer = ByteBuffer::from(output.stdout);
+            let mut command = Command::new(program);
+            command
+                .args(args)
+                .stdin(Stdio::piped())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped());
 
-            Ok(buffer)
+            if let Some(cwd) = arguments.optional_path("cwd")? {
+                command.current_dir(cwd);
+            } else if let Some(directory) = arguments.source_directory() {
+                command.current_dir(directory);
+            }
+
+            for (name, value) in arguments.prefixed("env_") {
+                let value = value
+                    .to_string()
+                    .map_err(|e| arguments.invalid(name, e.to_string()))?;
+                command.env(name, value);
+            }
+
+            let failed = |message: String| Error::CommandFailed {
+                command: argv.join(" "),
+                message,
+            };
+
+            let mut child = command.spawn().map_err(|e| failed(e.to_string()))?;
+
+            // written from another thread, so a command filling its stdout pipe can't deadlock us
+            let stdin = arguments
+                .get("stdin")
+                .map(ByteBuffer::to_vec)
+                .unwrap_or_default();
+            let mut child_stdin = child.stdin.take().expect("stdin must be piped");
+            let writer = std::thread::spawn(move || child_stdin.write_all(&stdin));
+
+            let output = child
+                .wait_with_output()
+                .map_err(|e| failed(e.to_string()))?;
+            // command may exit without reading its stdin, broken pipe is not an error then
+            let _ = writer.join();
+
+            if !output.status.success() {
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                let stderr = stderr.trim_end();
+                return Err(failed(if stderr.is_empty() {
+                    output.status.to_string()
+                } else {
+                    format!("{}\n{}", output.status, stderr)
+                }));
+            }
+
+            let mut stdout = output.stdout;
+            if arguments.usize("trim")? != 0 {
+                while stdout
+                    .last()
+                    .is_some_and(|byte| *byte == b'\n' || *byte == b'\r')
+                {
+                    stdout.pop();
+                }
+            }
+
+            Ok(ByteBuffer::from(stdout))
         },
     )
 }
 
 pub(crate) fn create_read_file_native_function() -> NativeFunction {
     NativeFunction::new(
-        NativeFunctionSignature::new("read_file"),
+        NativeFunctionSignature::new("read_file")
+            .with_doc("Raw bytes of the file, optionally sliced to length bytes starting at offset")
+            .with_param(Param::positional("path", Kind::Path))
+            .with_param(Param::named("offset", Kind::Integer).with_default(vec![0]))
+            .with_param(Param::named("length", Kind::Integer).optional()),
         |arguments, _| {
-            let arg0 = arguments.get_argument_at(0, "read_file")?;
+            let file_path = arguments.path("path")?;
 
-            let file_path = arg0
-                .to_string()
-                .map_err(|e| Error::Unknown(e.to_string()))?;
+            let read_error = |e: std::io::Error| Error::Io {
+                path: file_path.clone(),
+                message: e.to_string(),
+            };
 
-            let mut file = File::open(file_path)
-                .map_err(|e| Error::Unknown(format!("Error executing command: {}", e)))?;
+            let mut file = File::open(&file_path).map_err(read_error)?;
+            let file_length = file.metadata().map_err(read_error)?.len();
 
-            let mut buf_string = String::new();
-            file.read_to_string(&mut buf_string)
-                .map_err(|e| Error::Unknown(format!("Error executing command: {}", e)))?;
+            let offset = arguments.usize("offset")? as u64;
+            let length = match arguments.optional_usize("length")? {
+                Some(length) => length as u64,
+                None => file_length.saturating_sub(offset),
+            };
 
-            let buffer = ByteBuffer::from(buf_string.as_bytes().to_vec());
+            if offset.checked_add(length).is_none_or(|end| end > file_length) {
+                return Err(arguments.invalid(
+                    "length",
+                    format!(
+                        "range {}..{} is out of bounds of {} bytes long file",
+                        offset,
+                        offset.saturating_add(length),
+                        file_length
+                    ),
+                ));
+            }
 
-            Ok(buffer)
+            let mut bytes = Vec::new();
+            file.seek(SeekFrom::Start(offset)).map_err(read_error)?;
+            file.take(length).read_to_end(&mut bytes).map_err(read_error)?;
+
+            Ok(ByteBuffer::from(bytes))
         },
     )
 }
 
 pub(crate) fn create_pad_native_function() -> NativeFunction {
     NativeFunction::new(
-        NativeFunctionSignature::new("pad"),
-        |arguments, _| {
-            let mut buffer = arguments.get_argument_at(0, "pad")?.clone();
-
-            let left_padding = arguments.get_named_argument("left").map(|b| b.as_usize_unsafe());
-            let right_padding = arguments.get_named_argument("right").map(|b| b.as_usize_unsafe());
+        NativeFunctionSignature::new("pad")
+            .with_doc("Pads value with zeros on the left and on the right up to given sizes")
+            .with_param(Param::positional("value", Kind::Bytes))
+            .with_param(Param::named("left", Kind::Integer).optional())
+            .with_param(Param::named("right", Kind::Integer).optional()),
+        |arguments, compiler| {
+            let mut buffer = arguments.bytes("value")?.clone();
+            let limit = compiler.context().max_output_size();
 
-            if let Some(size) = left_padding {
+            if let Some(size) = arguments.optional_usize("left")? {
+                arguments.output_size(Some(size), limit)?;
                 buffer.pad_left(size);
             }
-            if let Some(size) = right_padding {
+            if let Some(size) = arguments.optional_usize("right")? {
+                arguments.output_size(Some(size), limit)?;
                 buffer.pad_right(size);
             }
 
@@ -111,3 +247,513 @@ pub(crate) fn create_pad_native_function() -> NativeFunction {
         },
     )
 }
+
+pub(crate) fn create_repeat_native_function() -> NativeFunction {
+    NativeFunction::new(
+        NativeFunctionSignature::new("repeat")
+            .with_doc("Value repeated count times")
+            .with_param(Param::positional("value", Kind::Bytes))
+            .with_param(Param::positional("count", Kind::Integer)),
+        |arguments, compiler| {
+            let value = arguments.bytes("value")?.to_vec();
+            let count = arguments.usize("count")?;
+            let limit = compiler.context().max_output_size();
+            arguments.output_size(value.len().checked_mul(count), limit)?;
+
+            Ok(ByteBuffer::from(value.repeat(count)))
+        },
+    )
+}
+
+pub(crate) fn create_fill_native_function() -> NativeFunction {
+    NativeFunction::new(
+        NativeFunctionSignature::new("fill")
+            .with_doc("Reserves size bytes filled with pattern, last repetition is cut to fit")
+            .with_param(Param::positional("size", Kind::Integer))
+            .with_param(Param::named("pattern", Kind::Bytes).with_default(vec![0])),
+        |arguments, compiler| {
+            let size = arguments.usize("size")?;
+            fill_pattern(&arguments, compiler, size)
+        },
+    )
+}
+
+pub(crate) fn create_fill_to_native_function() -> NativeFunction {
+    NativeFunction::new(
+        NativeFunctionSignature::new("fill_to")
+            .with_doc("Fills with pattern up to the given output offset")
+            .with_param(Param::positional("end", Kind::Integer))
+            .with_param(Param::named("pattern", Kind::Bytes).with_default(vec![0]))
+            .with_param(Param::named("offset", Kind::Integer).optional())
+            .with_output_offset("offset"),
+        |arguments, compiler| {
+            let end = arguments.usize("end")?;
+            let offset = arguments.usize("offset")?;
+            if offset > end {
+                return Err(arguments.invalid(
+                    "end",
+                    format!("output is already {} bytes long", offset),
+                ));
+            }
+
+            fill_pattern(&arguments, compiler, end - offset)
+        },
+    )
+}
+
+pub(crate) fn create_align_native_function() -> NativeFunction {
+    NativeFunction::new(
+        NativeFunctionSignature::new("align")
+            .with_doc("Fills with pattern until output offset is a multiple of boundary")
+            .with_param(Param::positional("boundary", Kind::Integer))
+            .with_param(Param::named("pattern", Kind::Bytes).with_default(vec![0]))
+            .with_param(Param::named("offset", Kind::Integer).optional())
+            .with_output_offset("offset"),
+        |arguments, compiler| {
+            let boundary = arguments.usize("boundary")?;
+            if boundary == 0 {
+                return Err(arguments.invalid("boundary", "must not be zero".to_string()));
+            }
+            let offset = arguments.usize("offset")?;
+
+            let size = (boundary - offset % boundary) % boundary;
+            fill_pattern(&arguments, compiler, size)
+        },
+    )
+}
+
+pub(crate) fn create_offset_native_function() -> NativeFunction {
+    NativeFunction::new(
+        NativeFunctionSignature::new("offset")
+            .with_doc("Current output offset, as few big endian bytes as possible")
+            .with_param(Param::named("offset", Kind::Integer).optional())
+            .with_output_offset("offset"),
+        |arguments, _| {
+            let offset = BigInt::from(arguments.usize("offset")?);
+            Ok(ByteBuffer::from(offset.to_bytes_be().1))
+        },
+    )
+}
+
+/// [size] bytes of the `pattern` argument repeated, limited by `max_output_size` of the context
+fn fill_pattern(
+    arguments: &NativeFunctionArguments,
+    compiler: &HexoCompiler,
+    size: usize,
+) -> Result<ByteBuffer, Error> {
+    let pattern = arguments.bytes("pattern")?.to_vec();
+    if pattern.is_empty() {
+        return Err(arguments.invalid("pattern", "must not be empty".to_string()));
+    }
+    arguments.output_size(Some(size), compiler.context().max_output_size())?;
+
+    Ok(ByteBuffer::from(
+        pattern.into_iter().cycle().take(size).collect::<Vec<u8>>(),
+    ))
+}
+
+pub(crate) fn create_varint_native_functions() -> Vec<NativeFunction> {
+    VarIntEncoding::ALL
+        .into_iter()
+        .map(create_varint_native_function)
+        .collect()
+}
+
+fn create_varint_native_function(encoding: VarIntEncoding) -> NativeFunction {
+    let (kind, doc) = match encoding {
+        VarIntEncoding::UnsignedLeb128 => (Kind::Integer, "Unsigned LEB128 of value"),
+        VarIntEncoding::SignedLeb128 => (Kind::SignedInteger, "Signed LEB128 of value"),
+        VarIntEncoding::Varint => (Kind::Integer, "Protobuf varint of value"),
+        VarIntEncoding::VarintZigzag => (
+            Kind::SignedInteger,
+            "Protobuf varint of zigzag encoded value, as in sint32 and sint64",
+        ),
+        VarIntEncoding::Vlq => (
+            Kind::Integer,
+            "Big endian variable length quantity of value, as in MIDI",
+        ),
+    };
+
+    NativeFunction::new(
+        NativeFunctionSignature::new(encoding.name())
+            .with_doc(doc)
+            .with_param(Param::positional("value", kind)),
+        move |arguments, _| {
+            let value = if encoding.is_signed() {
+                arguments.signed_big_integer("value")?
+            } else {
+                arguments.big_integer("value")?
+            };
+            let bytes = encoding
+                .encode(&value)
+                .expect("unsigned values are read as non negative");
+
+            Ok(ByteBuffer::from(bytes))
+        },
+    )
+}
+
+pub(crate) fn create_text_encoding_native_functions() -> Vec<NativeFunction> {
+    TextEncoding::ALL
+        .into_iter()
+        .map(create_text_encoding_native_function)
+        .collect()
+}
+
+fn create_text_encoding_native_function(encoding: TextEncoding) -> NativeFunction {
+    let doc = match encoding {
+        TextEncoding::Utf16Le => "UTF-16LE encoded value, optionally starting with byte order mark",
+        TextEncoding::Utf16Be => "UTF-16BE encoded value, optionally starting with byte order mark",
+        TextEncoding::Utf32Le => "UTF-32LE encoded value, optionally starting with byte order mark",
+        TextEncoding::Utf32Be => "UTF-32BE encoded value, optionally starting with byte order mark",
+        TextEncoding::Latin1 => "ISO-8859-1 encoded value, fails on characters above U+00FF",
+        TextEncoding::Ascii => "ASCII encoded value, fails on characters above U+007F",
+        TextEncoding::ModifiedUtf8 => "Java Modified UTF-8 encoded value, as in class files",
+    };
+
+    let mut signature = NativeFunctionSignature::new(encoding.name())
+        .with_doc(doc)
+        .with_param(Param::positional("value", Kind::String));
+    if encoding.has_bom() {
+        signature = signature.with_param(Param::named("bom", Kind::Integer).with_default(vec![0]));
+    }
+
+    NativeFunction::new(signature, move |arguments, _| {
+        let bom = encoding.has_bom() && arguments.usize("bom")? != 0;
+        let bytes = encoding
+            .encode(&arguments.string("value")?, bom)
+            .map_err(|e| arguments.invalid("value", e.to_string()))?;
+
+        Ok(ByteBuffer::from(bytes))
+    })
+}
+
+pub(crate) fn create_null_terminated_native_function() -> NativeFunction {
+    NativeFunction::new(
+        NativeFunctionSignature::new("null_terminated")
+            .with_doc("Value followed by width zero bytes, 2 for UTF-16 strings")
+            .with_param(Param::positional("value", Kind::Bytes))
+            .with_param(Param::named("width", Kind::Integer).with_default(vec![1])),
+        |arguments, compiler| {
+            let mut buffer = arguments.bytes("value")?.clone();
+            let width = arguments.usize("width")?;
+            let limit = compiler.context().max_output_size();
+            arguments.output_size(buffer.len().checked_add(width), limit)?;
+
+            buffer.push_byte_buffer(&ByteBuffer::from(vec![0; width]));
+            Ok(buffer)
+        },
+    )
+}
+
+pub(crate) fn create_len_prefixed_native_function() -> NativeFunction {
+    NativeFunction::new(
+        NativeFunctionSignature::new("len_prefixed")
+            .with_doc(
+                "Value preceded by its length in bytes divided by unit, \
+                format is an integer format like u16le or a variable length encoding like uleb128",
+            )
+            .with_param(Param::positional("value", Kind::Bytes))
+            .with_param(Param::named("format", Kind::String).with_default(b"u8".to_vec()))
+            .with_param(Param::named("unit", Kind::Integer).with_default(vec![1])),
+        |arguments, compiler| {
+            let value = arguments.bytes("value")?;
+            let format = arguments.string("format")?;
+
+            let unit = arguments.usize("unit")?;
+            if unit == 0 || value.len() % unit != 0 {
+                return Err(arguments.invalid(
+                    "unit",
+                    format!("length {} is not a multiple of {}", value.len(), unit),
+                ));
+            }
+            let length = value.len() / unit;
+
+            let prefix = if let Some(integer_format) = IntegerFormat::parse(&format) {
+                integer_format.encode(length as i128).ok_or_else(|| {
+                    arguments.invalid(
+                        "value",
+                        format!("length {} doesn't fit {}", length, integer_format),
+                    )
+                })?
+            } else if let Some(encoding) = VarIntEncoding::parse(&format) {
+                encoding
+                    .encode(&BigInt::from(length))
+                    .expect("length is not negative")
+            } else {
+                return Err(arguments.invalid("format", format!("unknown format {}", format)));
+            };
+
+            let limit = compiler.context().max_output_size();
+            arguments.output_size(prefix.len().checked_add(value.len()), limit)?;
+
+            let mut buffer = ByteBuffer::from(prefix);
+            buffer.push_byte_buffer(value);
+            Ok(buffer)
+        },
+    )
+}
+
+pub(crate) fn create_crc8_native_function() -> NativeFunction {
+    create_crc_native_function("crc8", "CRC-8/SMBUS", CrcAlgorithm::CRC8_SMBUS)
+}
+
+pub(crate) fn create_crc16_native_function() -> NativeFunction {
+    create_crc_native_function("crc16", "CRC-16/ARC", CrcAlgorithm::CRC16_ARC)
+}
+
+pub(crate) fn create_crc32_native_function() -> NativeFunction {
+    create_crc_native_function("crc32", "CRC-32/ISO-HDLC", CrcAlgorithm::CRC32_ISO_HDLC)
+}
+
+pub(crate) fn create_crc64_native_function() -> NativeFunction {
+    create_crc_native_function("crc64", "CRC-64/XZ", CrcAlgorithm::CRC64_XZ)
+}
+
+/// CRC of [algorithm] width, every parameter of [algorithm] can be overridden by an argument
+fn create_crc_native_function(name: &str, preset: &str, algorithm: CrcAlgorithm) -> NativeFunction {
+    let width = algorithm.width as usize / 8;
+    let integer = move |value: u64| value.to_be_bytes()[8 - width..].to_vec();
+
+    NativeFunction::new(
+        NativeFunctionSignature::new(name)
+            .with_doc(&format!(
+                "Big endian {} of value or of the output between from and to labels, \
+                other polynomials and parameters can be selected by arguments",
+                preset
+            ))
+            .with_param(Param::positional("value", Kind::Bytes))
+            .with_param(Param::named("poly", Kind::Integer).with_default(integer(algorithm.poly)))
+            .with_param(Param::named("init", Kind::Integer).with_default(integer(algorithm.init)))
+            .with_param(
+                Param::named("xorout", Kind::Integer).with_default(integer(algorithm.xor_out)),
+            )
+            .with_param(
+                Param::named("refin", Kind::Integer).with_default(vec![algorithm.reflect_in as u8]),
+            )
+            .with_param(
+                Param::named("refout", Kind::Integer)
+                    .with_default(vec![algorithm.reflect_out as u8]),
+            )
+            .with_output_range("value"),
+        move |arguments, _| {
+            let algorithm = CrcAlgorithm {
+                width: algorithm.width,
+                poly: crc_parameter(&arguments, "poly", algorithm.mask())?,
+                init: crc_parameter(&arguments, "init", algorithm.mask())?,
+                reflect_in: arguments.usize("refin")? != 0,
+                reflect_out: arguments.usize("refout")? != 0,
+                xor_out: crc_parameter(&arguments, "xorout", algorithm.mask())?,
+            };
+            let checksum = algorithm.checksum(&arguments.bytes("value")?.to_vec());
+
+            Ok(ByteBuffer::from(integer(checksum)))
+        },
+    )
+}
+
+fn crc_parameter(arguments: &NativeFunctionArguments, name: &str, mask: u64) -> Result<u64, Error> {
+    let value = arguments.usize(name)? as u64;
+    if value > mask {
+        return Err(arguments.invalid(
+            name,
+            format!("value doesn't fit {} bits", mask.count_ones()),
+        ));
+    }
+
+    Ok(value)
+}
+
+pub(crate) fn create_adler32_native_function() -> NativeFunction {
+    NativeFunction::new(
+        NativeFunctionSignature::new("adler32")
+            .with_doc("Big endian Adler-32 of value or of the output between from and to labels")
+            .with_param(Param::positional("value", Kind::Bytes))
+            .with_output_range("value"),
+        |arguments, _| {
+            let checksum = adler32(&arguments.bytes("value")?.to_vec());
+            Ok(ByteBuffer::from(checksum.to_be_bytes().to_vec()))
+        },
+    )
+}
+
+pub(crate) fn create_fletcher16_native_function() -> NativeFunction {
+    NativeFunction::new(
+        NativeFunctionSignature::new("fletcher16")
+            .with_doc("Big endian Fletcher-16 of value or of the output between from and to labels")
+            .with_param(Param::positional("value", Kind::Bytes))
+            .with_output_range("value"),
+        |arguments, _| {
+            let checksum = fletcher16(&arguments.bytes("value")?.to_vec());
+            Ok(ByteBuffer::from(checksum.to_be_bytes().to_vec()))
+        },
+    )
+}
+
+pub(crate) fn create_fletcher32_native_function() -> NativeFunction {
+    NativeFunction::new(
+        NativeFunctionSignature::new("fletcher32")
+            .with_doc(
+                "Big endian Fletcher-32 of value or of the output between from and to labels, \
+                summing little endian 16 bit words",
+            )
+            .with_param(Param::positional("value", Kind::Bytes))
+            .with_output_range("value"),
+        |arguments, _| {
+            let checksum = fletcher32(&arguments.bytes("value")?.to_vec());
+            Ok(ByteBuffer::from(checksum.to_be_bytes().to_vec()))
+        },
+    )
+}
+
+#[cfg(feature = "digest")]
+pub(crate) fn create_digest_native_functions() -> Vec<NativeFunction> {
+    vec![
+        create_digest_native_function::<sha1::Sha1>("sha1", "SHA-1"),
+        create_digest_native_function::<sha2::Sha256>("sha256", "SHA-256"),
+        create_digest_native_function::<sha2::Sha512>("sha512", "SHA-512"),
+        create_digest_native_function::<md5::Md5>("md5", "MD5"),
+    ]
+}
+
+#[cfg(feature = "digest")]
+fn create_digest_native_function<D: sha2::Digest>(name: &str, algorithm: &str) -> NativeFunction {
+    NativeFunction::new(
+        NativeFunctionSignature::new(name)
+            .with_doc(&format!(
+                "Raw {} digest of value or of the output between from and to labels",
+                algorithm
+            ))
+            .with_param(Param::positional("value", Kind::Bytes))
+            .with_output_range("value"),
+        |arguments, _| {
+            let digest = D::digest(arguments.bytes("value")?.to_vec());
+            Ok(ByteBuffer::from(digest.to_vec()))
+        },
+    )
+}
+
+pub(crate) fn create_base_encoding_native_functions() -> Vec<NativeFunction> {
+    BaseAlphabet::ALL
+        .into_iter()
+        .flat_map(|alphabet| {
+            [
+                create_base_encode_native_function(alphabet),
+                create_base_decode_native_function(alphabet),
+            ]
+        })
+        .collect()
+}
+
+fn create_base_encode_native_function(alphabet: BaseAlphabet) -> NativeFunction {
+    // url safe base64 is mostly used in tokens and urls, where padding is dropped
+    let padding = alphabet != BaseAlphabet::Base64Url;
+
+    NativeFunction::new(
+        NativeFunctionSignature::new(alphabet.name())
+            .with_doc(&format!(
+                "{} text of value, padded with = unless padding is 00",
+                alphabet
+            ))
+            .with_param(Param::positional("value", Kind::Bytes))
+            .with_param(Param::named("padding", Kind::Integer).with_default(vec![padding as u8])),
+        move |arguments, _| {
+            let padding = arguments.usize("padding")? != 0;
+            let encoded = alphabet.encode(&arguments.bytes("value")?.to_vec(), padding);
+
+            Ok(ByteBuffer::from(encoded.into_bytes()))
+        },
+    )
+}
+
+fn create_base_decode_native_function(alphabet: BaseAlphabet) -> NativeFunction {
+    NativeFunction::new(
+        NativeFunctionSignature::new(&format!("from_{}", alphabet.name()))
+            .with_doc(&format!(
+                "Bytes of {} text, whitespace is ignored and padding is optional",
+                alphabet
+            ))
+            .with_param(Param::positional("value", Kind::String)),
+        move |arguments, _| {
+            let decoded = alphabet
+                .decode(&arguments.string("value")?)
+                .map_err(|e| arguments.invalid("value", e))?;
+
+            Ok(ByteBuffer::from(decoded))
+        },
+    )
+}
+
+pub(crate) fn create_hex_native_function() -> NativeFunction {
+    NativeFunction::new(
+        NativeFunctionSignature::new("hex")
+            .with_doc("Uppercase hex text of value with separator between bytes")
+            .with_param(Param::positional("value", Kind::Bytes))
+            .with_param(Param::named("separator", Kind::String).with_default(Vec::new())),
+        |arguments, _| {
+            let separator = arguments.string("separator")?;
+            let encoded = arguments
+                .bytes("value")?
+                .to_vec()
+                .iter()
+                .map(|byte| format!("{:02X}", byte))
+                .collect::<Vec<String>>()
+                .join(&separator);
+
+            Ok(ByteBuffer::from(encoded.into_bytes()))
+        },
+    )
+}
+
+pub(crate) fn create_from_hex_native_function() -> NativeFunction {
+    NativeFunction::new(
+        NativeFunctionSignature::new("from_hex")
+            .with_doc(
+                "Bytes of hex text, whitespace, , : - _ separators and 0x prefixes are ignored",
+            )
+            .with_param(Param::positional("value", Kind::String)),
+        |arguments, _| {
+            let digits = strip_hex_separators(&arguments.string("value")?);
+            let decoded =
+                decode_bytes_from_string(&digits).map_err(|e| arguments.invalid("value", e))?;
+
+            Ok(ByteBuffer::from(decoded))
+        },
+    )
+}
+
+pub(crate) fn create_pem_native_function() -> NativeFunction {
+    NativeFunction::new(
+        NativeFunctionSignature::new("pem")
+            .with_doc("PEM block of value with label, like CERTIFICATE")
+            .with_param(Param::positional("value", Kind::Bytes))
+            .with_param(Param::named("label", Kind::String)),
+        |arguments, _| {
+            let encoded = pem_encode(
+                &arguments.bytes("value")?.to_vec(),
+                &arguments.string("label")?,
+            );
+
+            Ok(ByteBuffer::from(encoded.into_bytes()))
+        },
+    )
+}
+
+pub(crate) fn create_from_pem_native_function() -> NativeFunction {
+    NativeFunction::new(
+        NativeFunctionSignature::new("from_pem")
+            .with_doc("Bytes of the first PEM block in value, or of the first one with label")
+            .with_param(Param::positional("value", Kind::String))
+            .with_param(Param::named("label", Kind::String).optional()),
+        |arguments, _| {
+            let label = arguments.optional_string("label")?;
+            let body = pem_body(&arguments.string("value")?, label.as_deref())
+                .map_err(|e| arguments.invalid("value", e))?;
+            let decoded = BaseAlphabet::Base64
+                .decode(&body)
+                .map_err(|e| arguments.invalid("value", e))?;
+
+            Ok(ByteBuffer::from(decoded))
+        },
+    )
+}
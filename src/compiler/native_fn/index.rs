@@ -1,6 +1,10 @@
 use crate::compiler::native_fn::signature::NativeFunction;
-use crate::compiler::native_fn::{create_cmd_native_function, create_len_native_function, create_pad_left_native_function, create_pad_native_function, create_pad_right_native_function, create_read_file_native_function, NativeFunctionDefinition};
+use crate::compiler::native_fn::{create_adler32_native_function, create_cmd_native_function, create_crc16_native_function, create_crc32_native_function, create_crc64_native_function, create_crc8_native_function, create_fletcher16_native_function, create_fletcher32_native_function, create_len_native_function, create_pad_left_native_function, create_pad_native_function, create_repeat_native_function, create_fill_native_function, create_fill_to_native_function, create_align_native_function, create_offset_native_function, create_pad_right_native_function, create_read_file_native_function, create_varint_native_functions, create_text_encoding_native_functions, create_null_terminated_native_function, create_len_prefixed_native_function, create_base_encoding_native_functions, create_hex_native_function, create_from_hex_native_function, create_pem_native_function, create_from_pem_native_function, NativeFunctionDefinition};
+#[cfg(feature = "digest")]
+use crate::compiler::native_fn::create_digest_native_functions;
 use crate::compiler::native_fn::implementation::EvalNativeFunctionDef;
+use crate::compiler::native_fn::Error;
+
 #[derive(Clone, Debug)]
 pub(crate) struct NativeFunctionIndex {
     functions: Vec<NativeFunction>,
@@ -16,8 +20,25 @@ impl Default for NativeFunctionIndex {
 
 impl NativeFunctionIndex {
 
+    pub(crate) fn functions(&self) -> &Vec<NativeFunction> {
+        &self.functions
+    }
+
     pub(crate) fn find(&self, name: String) -> Option<&NativeFunction> {
-        return self.functions.iter().find(|f| f.signature().name() == name);
+        self.functions.iter().find(|f| f.signature().name() == name)
+    }
+
+    /// Adds [function] to the index, its name must not be taken by a built-in or registered one
+    pub(crate) fn register(&mut self, function: NativeFunction) -> Result<(), Error> {
+        let name = function.signature().name();
+        if self.find(name.to_string()).is_some() {
+            return Err(Error::FunctionAlreadyDefined {
+                name: name.to_string(),
+            });
+        }
+
+        self.functions.push(function);
+        Ok(())
     }
 
     fn create_native_functions() -> Vec<NativeFunction> {
@@ -30,7 +51,30 @@ impl NativeFunctionIndex {
             create_cmd_native_function(),
             create_read_file_native_function(),
             create_pad_native_function(),
+            create_repeat_native_function(),
+            create_fill_native_function(),
+            create_fill_to_native_function(),
+            create_align_native_function(),
+            create_offset_native_function(),
+            create_crc8_native_function(),
+            create_crc16_native_function(),
+            create_crc32_native_function(),
+            create_crc64_native_function(),
+            create_adler32_native_function(),
+            create_fletcher16_native_function(),
+            create_fletcher32_native_function(),
+            create_null_terminated_native_function(),
+            create_len_prefixed_native_function(),
+            create_hex_native_function(),
+            create_from_hex_native_function(),
+            create_pem_native_function(),
+            create_from_pem_native_function(),
         ];
+        direct_fn.append(&mut create_varint_native_functions());
+        direct_fn.append(&mut create_text_encoding_native_functions());
+        direct_fn.append(&mut create_base_encoding_native_functions());
+        #[cfg(feature = "digest")]
+        direct_fn.append(&mut create_digest_native_functions());
         direct_fn.append(
             &mut definitions.iter().map(|d| d.create()).collect()
         );
@@ -39,7 +83,7 @@ impl NativeFunctionIndex {
     }
 
     fn create_native_function_definitions() -> Vec<Box<dyn NativeFunctionDefinition>> {
-        return vec![
+        vec![
             Box::new(EvalNativeFunctionDef::default()),
         ]
     }
@@ -1,12 +1,14 @@
 mod error;
 mod implementations;
 mod index;
+mod parameter;
 mod signature;
 pub(crate) mod implementation;
 mod arguments;
 
-pub(crate) use error::Error;
+pub use error::Error;
 pub(crate) use implementations::*;
 pub(crate) use index::*;
-pub(crate) use signature::*;
-pub(crate) use arguments::*;
\ No newline at end of file
+pub use arguments::NativeFunctionArguments;
+pub use parameter::{NativeFunctionParameter, NativeParameterKind};
+pub use signature::{NativeFunction, NativeFunctionDefinition, NativeFunctionSignature};
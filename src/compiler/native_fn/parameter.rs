@@ -0,0 +1,147 @@
+use std::fmt::{Display, Formatter};
+
+use crate::util::byte_buffer::ByteBuffer;
+
+/// How argument bytes are interpreted by the function, checked before the call
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum NativeParameterKind {
+    Bytes,
+    /// Unsigned big endian integer
+    Integer,
+    /// Two's complement big endian integer as wide as its bytes, like typed number `10x-1:i16`
+    SignedInteger,
+    /// UTF-8 string
+    String,
+    /// UTF-8 path, relative paths are resolved against directory of the calling source file
+    Path,
+}
+
+impl Display for NativeParameterKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            NativeParameterKind::Bytes => "bytes",
+            NativeParameterKind::Integer => "integer",
+            NativeParameterKind::SignedInteger => "signed integer",
+            NativeParameterKind::String => "string",
+            NativeParameterKind::Path => "path",
+        };
+
+        f.write_str(name)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum NativeParameterBinding {
+    /// Passed by position or by name
+    Positional,
+    /// Passed by name only
+    Named,
+    /// Collects all positional arguments following declared positional parameters
+    Variadic,
+    /// Collects all named arguments starting with parameter name, like `env_HOME`
+    Prefixed,
+}
+
+#[derive(Clone, Debug)]
+pub struct NativeFunctionParameter {
+    name: String,
+    kind: NativeParameterKind,
+    binding: NativeParameterBinding,
+    required: bool,
+    default: Option<ByteBuffer>,
+}
+
+impl NativeFunctionParameter {
+    /// Required parameter passed by position or by name
+    pub fn positional(name: &str, kind: NativeParameterKind) -> NativeFunctionParameter {
+        Self::new(name, kind, NativeParameterBinding::Positional, true)
+    }
+
+    /// Required parameter passed by name only
+    pub fn named(name: &str, kind: NativeParameterKind) -> NativeFunctionParameter {
+        Self::new(name, kind, NativeParameterBinding::Named, true)
+    }
+
+    /// Collects positional arguments following the positional parameters
+    pub fn variadic(name: &str, kind: NativeParameterKind) -> NativeFunctionParameter {
+        Self::new(name, kind, NativeParameterBinding::Variadic, false)
+    }
+
+    /// Collects named arguments starting with [prefix]
+    pub fn prefixed(prefix: &str, kind: NativeParameterKind) -> NativeFunctionParameter {
+        Self::new(prefix, kind, NativeParameterBinding::Prefixed, false)
+    }
+
+    fn new(
+        name: &str,
+        kind: NativeParameterKind,
+        binding: NativeParameterBinding,
+        required: bool,
+    ) -> NativeFunctionParameter {
+        NativeFunctionParameter {
+            name: name.to_string(),
+            kind,
+            binding,
+            required,
+            default: None,
+        }
+    }
+
+    /// Makes the parameter optional, executor gets nothing if the argument is omitted
+    pub fn optional(self) -> NativeFunctionParameter {
+        NativeFunctionParameter {
+            required: false,
+            ..self
+        }
+    }
+
+    /// Makes the parameter optional, executor gets [default] if the argument is omitted
+    pub fn with_default(self, default: Vec<u8>) -> NativeFunctionParameter {
+        NativeFunctionParameter {
+            required: false,
+            default: Some(ByteBuffer::from(default)),
+            ..self
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn kind(&self) -> NativeParameterKind {
+        self.kind
+    }
+
+    pub(crate) fn binding(&self) -> NativeParameterBinding {
+        self.binding
+    }
+
+    pub fn is_required(&self) -> bool {
+        self.required
+    }
+
+    pub(crate) fn default(&self) -> Option<&ByteBuffer> {
+        self.default.as_ref()
+    }
+}
+
+impl Display for NativeFunctionParameter {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.binding {
+            NativeParameterBinding::Variadic => write!(f, "{}: {}...", self.name, self.kind),
+            NativeParameterBinding::Prefixed => write!(f, "{}*: {}", self.name, self.kind),
+            _ => {
+                let optional = if self.required || self.default.is_some() { "" } else { "?" };
+                write!(f, "{}{}: {}", self.name, optional, self.kind)?;
+
+                if let Some(default) = &self.default {
+                    let bytes: Vec<String> =
+                        default.to_vec().iter().map(|b| format!("{:02x}", b)).collect();
+                    write!(f, " = {}", bytes.join(" "))?;
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
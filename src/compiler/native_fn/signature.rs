@@ -1,64 +1,283 @@
+use std::collections::HashMap;
+use std::fmt::{Debug, Display, Formatter};
+use std::path::Path;
+use std::sync::Arc;
+
 use crate::compiler::native_fn::error::Error;
+use crate::compiler::native_fn::parameter::{
+    NativeFunctionParameter, NativeParameterBinding, NativeParameterKind,
+};
 use crate::util::byte_buffer::ByteBuffer;
-use std::collections::HashMap;
 use crate::compiler::HexoCompiler;
 use crate::compiler::native_fn::arguments::NativeFunctionArguments;
 
 #[derive(Clone, Debug)]
-pub(crate) struct NativeFunctionSignature {
+pub struct NativeFunctionSignature {
     name: String,
-    is_safe: bool
+    is_safe: bool,
+    doc: String,
+    params: Vec<NativeFunctionParameter>,
+    /// Parameter that can be taken from the output between `from` and `to` labels instead
+    output_range: Option<String>,
+    /// Parameter that defaults to offset of the call in the output being built
+    output_offset: Option<String>,
 }
 
 impl NativeFunctionSignature {
-    pub(crate) fn new(name: &str) -> NativeFunctionSignature {
+    pub fn new(name: &str) -> NativeFunctionSignature {
         NativeFunctionSignature {
             name: String::from(name),
-            is_safe: true
+            is_safe: true,
+            doc: String::new(),
+            params: Vec::new(),
+            output_range: None,
+            output_offset: None,
         }
     }
 
-    pub(crate) fn new_unsafe(name: &str) -> NativeFunctionSignature {
+    pub fn new_unsafe(name: &str) -> NativeFunctionSignature {
         NativeFunctionSignature {
-            name: String::from(name),
-            is_safe: false
+            is_safe: false,
+            ..Self::new(name)
+        }
+    }
+
+    pub fn with_doc(self, doc: &str) -> NativeFunctionSignature {
+        NativeFunctionSignature {
+            doc: doc.to_string(),
+            ..self
         }
     }
 
-    pub(crate) fn name(&self) -> &str {
+    pub fn with_param(mut self, param: NativeFunctionParameter) -> NativeFunctionSignature {
+        self.params.push(param);
+        self
+    }
+
+    /// Lets [param] be passed as `from` and `to` labels, the function is then evaluated
+    /// over that range of the final output. Length of the result must not depend on [param]
+    pub fn with_output_range(self, param: &str) -> NativeFunctionSignature {
+        NativeFunctionSignature {
+            output_range: Some(param.to_string()),
+            ..self
+        }
+    }
+
+    /// Lets [param] default to the offset of the call in the output being built, or in the
+    /// constant being defined. The parameter itself must be declared as optional
+    pub fn with_output_offset(self, param: &str) -> NativeFunctionSignature {
+        NativeFunctionSignature {
+            output_offset: Some(param.to_string()),
+            ..self
+        }
+    }
+
+    pub fn name(&self) -> &str {
         &self.name
     }
 
-    pub(crate) fn is_safe(&self) -> bool {
+    pub fn is_safe(&self) -> bool {
         self.is_safe
     }
+
+    pub fn doc(&self) -> &str {
+        &self.doc
+    }
+
+    pub(crate) fn output_range(&self) -> Option<&str> {
+        self.output_range.as_deref()
+    }
+
+    pub(crate) fn output_offset(&self) -> Option<&str> {
+        self.output_offset.as_deref()
+    }
+
+    /// Matches [arguments] of a call against declared parameters, filling in defaults
+    pub(crate) fn bind_arguments<'a>(
+        &'a self,
+        arguments: &HashMap<String, ByteBuffer>,
+        source_path: &'a Path,
+    ) -> Result<NativeFunctionArguments<'a>, Error> {
+        let mut remaining = arguments.clone();
+        let mut values = HashMap::new();
+        let mut variadic = Vec::new();
+
+        let mut position = 0;
+        for param in self.params_with(NativeParameterBinding::Positional) {
+            let by_position = remaining.remove(&position.to_string());
+            let by_name = remaining.remove(param.name());
+            position += 1;
+
+            match (by_position, by_name) {
+                (Some(_), Some(_)) => {
+                    return Err(Error::DuplicateArgument {
+                        name: param.name().to_string(),
+                        function_name: self.name.clone(),
+                    });
+                }
+                (Some(value), None) | (None, Some(value)) => {
+                    values.insert(param.name().to_string(), value);
+                }
+                (None, None) => {}
+            }
+        }
+
+        if self.params_with(NativeParameterBinding::Variadic).next().is_some() {
+            while let Some(value) = remaining.remove(&position.to_string()) {
+                variadic.push(value);
+                position += 1;
+            }
+        }
+
+        for param in self.params_with(NativeParameterBinding::Named) {
+            if let Some(value) = remaining.remove(param.name()) {
+                values.insert(param.name().to_string(), value);
+            }
+        }
+
+        for param in self.params_with(NativeParameterBinding::Prefixed) {
+            let prefixed: Vec<String> = remaining
+                .keys()
+                .filter(|name| name.starts_with(param.name()))
+                .cloned()
+                .collect();
+            for name in prefixed {
+                let value = remaining.remove(&name).expect("name is taken from the map");
+                values.insert(name, value);
+            }
+        }
+
+        if let Some(name) = remaining.keys().min() {
+            return Err(Error::UnknownArgument {
+                name: name.clone(),
+                function_name: self.name.clone(),
+                signature: self.to_string(),
+            });
+        }
+
+        for param in &self.params {
+            if values.contains_key(param.name()) {
+                continue;
+            }
+
+            if let Some(default) = param.default() {
+                values.insert(param.name().to_string(), default.clone());
+            } else if param.is_required() {
+                return Err(Error::MissingArgument {
+                    name: param.name().to_string(),
+                    function_name: self.name.clone(),
+                    signature: self.to_string(),
+                });
+            }
+        }
+
+        for (name, value) in &values {
+            self.check_kind(name, self.param_kind(name), value)?;
+        }
+        if let Some(param) = self.params_with(NativeParameterBinding::Variadic).next() {
+            for value in &variadic {
+                self.check_kind(param.name(), param.kind(), value)?;
+            }
+        }
+
+        Ok(NativeFunctionArguments::new(
+            self,
+            values,
+            variadic,
+            source_path,
+        ))
+    }
+
+    fn params_with(
+        &self,
+        binding: NativeParameterBinding,
+    ) -> impl Iterator<Item = &NativeFunctionParameter> {
+        self.params.iter().filter(move |param| param.binding() == binding)
+    }
+
+    fn param_kind(&self, name: &str) -> NativeParameterKind {
+        self.params
+            .iter()
+            .find(|param| match param.binding() {
+                NativeParameterBinding::Prefixed => name.starts_with(param.name()),
+                _ => param.name() == name,
+            })
+            .map(|param| param.kind())
+            .unwrap_or(NativeParameterKind::Bytes)
+    }
+
+    fn check_kind(
+        &self,
+        name: &str,
+        kind: NativeParameterKind,
+        value: &ByteBuffer,
+    ) -> Result<(), Error> {
+        match kind {
+            NativeParameterKind::String | NativeParameterKind::Path => value
+                .to_string()
+                .map(|_| ())
+                .map_err(|e| Error::InvalidArgument {
+                    name: name.to_string(),
+                    function_name: self.name.clone(),
+                    message: format!("expected {}, {}", kind, e),
+                }),
+            NativeParameterKind::Bytes
+            | NativeParameterKind::Integer
+            | NativeParameterKind::SignedInteger => Ok(()),
+        }
+    }
 }
 
-type NativeFunctionExecutor = fn(NativeFunctionArguments, &HexoCompiler) -> Result<ByteBuffer, Error>;
+impl Display for NativeFunctionSignature {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let params: Vec<String> = self.params.iter().map(|p| p.to_string()).collect();
+        write!(f, "{}({})", self.name, params.join(", "))
+    }
+}
 
-#[derive(Clone, Debug)]
-pub(crate) struct NativeFunction {
+/// Body of a native function, may capture state like lookup tables
+pub(crate) type NativeFunctionExecutor = Arc<
+    dyn Fn(NativeFunctionArguments, &HexoCompiler) -> Result<ByteBuffer, Error> + Send + Sync,
+>;
+
+#[derive(Clone)]
+pub struct NativeFunction {
     signature: NativeFunctionSignature,
     executor: NativeFunctionExecutor,
 }
 
 impl NativeFunction {
-    pub(crate) fn new(signature: NativeFunctionSignature, executor: NativeFunctionExecutor) -> NativeFunction {
+    pub fn new<F>(signature: NativeFunctionSignature, executor: F) -> NativeFunction
+    where
+        F: Fn(NativeFunctionArguments, &HexoCompiler) -> Result<ByteBuffer, Error>
+            + Send
+            + Sync
+            + 'static,
+    {
         NativeFunction {
             signature: signature,
-            executor: executor,
+            executor: Arc::new(executor),
         }
     }
 
-    pub(crate) fn signature(&self) -> &NativeFunctionSignature {
+    pub fn signature(&self) -> &NativeFunctionSignature {
         &self.signature
     }
 
-    pub(crate) fn executor(&self) -> NativeFunctionExecutor {
-        self.executor
+    pub(crate) fn executor(&self) -> &NativeFunctionExecutor {
+        &self.executor
+    }
+}
+
+impl Debug for NativeFunction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NativeFunction")
+            .field("signature", &self.signature)
+            .finish_non_exhaustive()
     }
 }
 
-pub(crate) trait NativeFunctionDefinition {
+/// Factory of a [NativeFunction], lets embedders keep function state in their own types
+pub trait NativeFunctionDefinition {
     fn create(&self) -> NativeFunction;
 }
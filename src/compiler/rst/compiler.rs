@@ -1,33 +1,117 @@
-use crate::util::byte_buffer::ByteBuffer;
-use std::collections::HashMap;
-use std::path::Path;
+use crate::util::byte_buffer::{ByteBuffer, ByteMarker};
+use std::cell::{Cell, RefCell};
+use num_bigint::BigInt;
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
 use crate::compiler::cst::{
-    CstActualParameter, CstAtom, CstAtomVec, CstEmitStatement, CstFile, CstFunctionStatement,
+    CstActualParameter, CstAssertStatement, CstAtom, CstAtomVec, CstCondition,
+    CstConditionalStatement, CstConstantStatement, CstDeclaration, CstEmitStatement,
+    CstExpression, CstFile, CstFunctionStatement, CstIncludeStatement, CstLoopSource,
+    CstLoopStatement,
 };
 use crate::compiler::rst::scope::{
-    CompilationScope, ConstantBinding, FunctionBinding,
+    CompilationScope, ConditionalBinding, ConstantBinding, FunctionBinding,
 };
+use crate::compiler::rst::diagnostics::{Diagnostics, Poisoned};
 use crate::compiler::rst::error::Error;
+use crate::compiler::rst::Warning;
+use crate::compiler::rst::expression::{
+    encode_integer, integer_from_bytes, push_integer, Expression,
+};
+use crate::compiler::native_fn::{Error as NativeFunctionError, NativeFunction};
 use crate::compiler::rst::node::HexoFile;
-use crate::compiler::HexoCompiler;
-use crate::compiler::native_fn::NativeFunctionArguments;
+use crate::compiler::{CompilerSource, HexoCompiler, HexoCompilerContext};
+use crate::compiler::span::Span;
 use crate::util::id::HexoId;
+use crate::util::integer_format::IntegerFormat;
+use crate::util::logger;
+
+/// Meaning of a [ByteMarker] placed by the compiler
+enum LabelMarker {
+    Definition { name: String, span: Span },
+    Reference {
+        expression: Expression,
+        format: IntegerFormat,
+        span: Span,
+    },
+    OutputRange(Box<OutputRange>),
+}
+
+/// Call of a native function over the output between [from] and [to]
+struct OutputRange {
+    function: NativeFunction,
+    range_param: String,
+    params: HashMap<String, ByteBuffer>,
+    from: Expression,
+    to: Expression,
+    span: Span,
+}
 
 pub(crate) struct RstCompiler<'a> {
     parent: &'a HexoCompiler,
-    safe_mode: bool,
+    context: &'a HexoCompilerContext,
+    /// Compiled source, it opens included files
+    source: &'a dyn CompilerSource,
+    diagnostics: Diagnostics,
+    /// Scope of the compiled file, the only one where [definitions] are bound
+    root_scope_id: HexoId,
+    /// Constants given outside of the source, they replace constants of the same name
+    definitions: Vec<CstConstantStatement>,
+    /// Names of [definitions] that replaced a constant of the file or were referenced by it
+    used_definitions: RefCell<HashSet<String>>,
+    /// Files currently being included, starting with the compiled file
+    include_chain: RefCell<Vec<PathBuf>>,
+    label_markers: RefCell<HashMap<HexoId, LabelMarker>>,
+    /// Names of user functions currently being called, outermost first
+    call_stack: RefCell<Vec<String>>,
+    /// Set while `#if` conditions are decided, there is no output offset to pass to functions then
+    deciding_condition: Cell<bool>,
 }
 
 impl RstCompiler<'_> {
-    pub(crate) fn new(parent: &HexoCompiler, safe_mode: bool) -> RstCompiler {
-        RstCompiler { parent, safe_mode }
+    pub(crate) fn new<'a>(
+        parent: &'a HexoCompiler,
+        context: &'a HexoCompilerContext,
+        source: &'a dyn CompilerSource,
+        definitions: Vec<CstConstantStatement>,
+    ) -> RstCompiler<'a> {
+        RstCompiler {
+            parent,
+            context,
+            source,
+            diagnostics: Diagnostics::default(),
+            root_scope_id: HexoId::next(),
+            definitions,
+            used_definitions: RefCell::new(HashSet::new()),
+            include_chain: RefCell::new(Vec::new()),
+            label_markers: RefCell::new(HashMap::new()),
+            call_stack: RefCell::new(Vec::new()),
+            deciding_condition: Cell::new(false),
+        }
     }
 
-    pub(crate) fn compile(&self, cst: &CstFile) -> Result<HexoFile, Error> {
-        let scope_id = HexoId::next();
-        let mut scope = self.build_scope(scope_id, cst.main())?;
+    /// Compiles [cst] into binary output, reporting every error found along the way
+    pub(crate) fn compile(self, cst: &CstFile) -> Result<HexoFile, Vec<Error>> {
+        logger::debug!("Compiling RST of {:?}", cst.path());
+        self.include_chain
+            .borrow_mut()
+            .push(Self::canonical_path(cst.path()));
+
+        let scope_id = self.root_scope_id;
+        let mut scope = CompilationScope::default();
+        self.build_scope_definitions_into(scope_id, &mut scope);
+        self.build_scope_into(scope_id, &cst.main(), &mut scope);
+        self.warn_unused_definitions();
 
-        let bb = self.build_bytes(scope_id, &mut scope, cst.main().emits())?;
+        let mut bb = self.build_bytes(scope_id, &mut scope, cst.main().emits());
+        self.resolve_labels(&mut bb);
+        self.parent.add_warnings(self.diagnostics.take_warnings());
+
+        if self.diagnostics.has_errors() {
+            return Err(self.diagnostics.into_errors());
+        }
 
         Ok(HexoFile::new(bb))
     }
@@ -37,14 +121,15 @@ impl RstCompiler<'_> {
         scope_id: HexoId,
         scope: &mut CompilationScope,
         emits: &Vec<CstEmitStatement>,
-    ) -> Result<ByteBuffer, Error> {
+    ) -> ByteBuffer {
         let mut byte_buffer = ByteBuffer::default();
 
         for emit in emits {
-            self.build_bytes_into(scope_id, scope, emit.atoms(), &mut byte_buffer)?
+            // failure is already reported, keep going to find errors in the following statements
+            let _ = self.build_bytes_into(scope_id, scope, emit.atoms(), &mut byte_buffer);
         }
 
-        Ok(byte_buffer)
+        byte_buffer
     }
 
     fn build_bytes_into(
@@ -53,113 +138,836 @@ impl RstCompiler<'_> {
         scope: &mut CompilationScope,
         atoms: &CstAtomVec,
         buffer: &mut ByteBuffer,
-    ) -> Result<(), Error> {
+    ) -> Result<(), Poisoned> {
+        let mut result = Ok(());
+
         for atom in atoms {
-            match atom {
-                CstAtom::Hex(byte) => buffer.push_byte(*byte),
-                CstAtom::String(string) => buffer.push_string(string.clone()),
-                CstAtom::Number(number) => buffer.push_u32_shrunk(*number),
-                CstAtom::Constant { name } => {
-                    Self::build_constant_into(scope_id, scope, name, buffer)?
+            // keep evaluating the rest of atoms to report all of their errors
+            result = result.and(self.build_atom_into(scope_id, scope, atom, buffer));
+        }
+
+        result
+    }
+
+    fn build_atom_into(
+        &self,
+        scope_id: HexoId,
+        scope: &mut CompilationScope,
+        atom: &CstAtom,
+        buffer: &mut ByteBuffer,
+    ) -> Result<(), Poisoned> {
+        match atom {
+            CstAtom::Hex(byte) => buffer.push_byte(*byte),
+            CstAtom::String(string) => buffer.push_string(string.clone()),
+            CstAtom::Number(number) => buffer.push_u32_shrunk(*number),
+            CstAtom::Integer { bytes, format } => push_integer(bytes, *format, buffer),
+            CstAtom::Constant { name, span } => {
+                self.build_constant_into(scope_id, scope, name, span, buffer)?
+            }
+            CstAtom::Function { name, params, span } => {
+                self.build_function_into(scope_id, scope, name.clone(), params, span, buffer)?
+            }
+            CstAtom::Label { name, span } => {
+                let marker = LabelMarker::Definition {
+                    name: name.clone(),
+                    span: span.clone(),
+                };
+                self.push_label_marker(marker, 0, buffer)
+            }
+            CstAtom::LabelReference { name, format, span } => {
+                let marker = LabelMarker::Reference {
+                    expression: Expression::Label {
+                        name: name.clone(),
+                        span: span.clone(),
+                    },
+                    format: *format,
+                    span: span.clone(),
+                };
+                self.push_label_marker(marker, format.width(), buffer)
+            }
+            CstAtom::Expression {
+                expression,
+                format,
+                span,
+            } => self.build_expression_into(scope_id, scope, expression, *format, span, buffer)?,
+            CstAtom::Conditional(conditional) => {
+                self.build_conditional_into(scope_id, scope, conditional, buffer)?
+            }
+            CstAtom::Loop(statement) => self.build_loop_into(scope_id, scope, statement, buffer)?,
+            CstAtom::Assertion(statement) => {
+                self.build_assertion_into(scope_id, scope, statement, buffer)?
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Emits the branch taken when the scope was built, see [Self::build_scope_conditionals_into]
+    fn build_conditional_into(
+        &self,
+        scope_id: HexoId,
+        scope: &mut CompilationScope,
+        conditional: &CstConditionalStatement,
+        buffer: &mut ByteBuffer,
+    ) -> Result<(), Poisoned> {
+        let Some(binding) = scope.get_local_conditional(scope_id, conditional.id()) else {
+            return Ok(());
+        };
+        if binding.poisoned {
+            return Err(Poisoned);
+        }
+        let Some(branch) = binding.branch else {
+            return Ok(());
+        };
+
+        let mut result = Ok(());
+        for emit in conditional.branches()[branch].body().emits() {
+            result = result.and(self.build_bytes_into(scope_id, scope, emit.atoms(), buffer));
+        }
+
+        result
+    }
+
+    /// Emits body of [statement] once for every value of its source
+    fn build_loop_into(
+        &self,
+        scope_id: HexoId,
+        scope: &mut CompilationScope,
+        statement: &CstLoopStatement,
+        buffer: &mut ByteBuffer,
+    ) -> Result<(), Poisoned> {
+        let values = self.evaluate_loop_source(scope_id, scope, statement)?;
+
+        for value in values {
+            // like function calls, every iteration gets its own scope with the loop variable
+            let iteration_id = HexoId::next();
+            scope.bind_parents(iteration_id, vec![scope_id]);
+            scope.bind_local_constant(
+                iteration_id,
+                ConstantBinding {
+                    name: statement.variable().to_string(),
+                    byte_buffer: value,
+                    poisoned: false,
+                },
+            );
+            self.build_scope_into(iteration_id, &statement.body(), scope);
+
+            let mut result = Ok(());
+            for emit in statement.body().emits() {
+                let emit_result = self.build_bytes_into(iteration_id, scope, emit.atoms(), buffer);
+                result = result.and(emit_result);
+            }
+            scope.remove_local_scope(iteration_id);
+
+            // the same errors would be reported by every following iteration
+            result?;
+        }
+
+        Ok(())
+    }
+
+    /// Checks [statement] against [buffer] built so far, so output offsets are known in it
+    fn build_assertion_into(
+        &self,
+        scope_id: HexoId,
+        scope: &mut CompilationScope,
+        statement: &CstAssertStatement,
+        buffer: &mut ByteBuffer,
+    ) -> Result<(), Poisoned> {
+        let span = statement.span();
+        if self.evaluate_condition(scope_id, scope, statement.condition(), span, buffer)? {
+            return Ok(());
+        }
+
+        let message = match statement.message() {
+            Some(atoms) => {
+                let message =
+                    self.evaluate_condition_value(scope_id, scope, atoms, span, buffer)?;
+                Some(String::from_utf8_lossy(&message).to_string())
+            }
+            None => None,
+        };
+
+        Err(self.diagnostics.report(Error::AssertionFailed {
+            message,
+            span: span.clone(),
+        }))
+    }
+
+    /// Values of the loop variable, their count is limited by `max_loop_iterations`
+    fn evaluate_loop_source(
+        &self,
+        scope_id: HexoId,
+        scope: &mut CompilationScope,
+        statement: &CstLoopStatement,
+    ) -> Result<Vec<ByteBuffer>, Poisoned> {
+        let span = statement.span();
+        let evaluate = |atoms: &CstAtomVec, scope: &mut CompilationScope| {
+            let mut buffer = ByteBuffer::default();
+            self.build_bytes_into(scope_id, scope, atoms, &mut buffer)?;
+
+            if !buffer.markers().is_empty() {
+                return Err(self.diagnostics.report(Error::LabelInLoopSource {
+                    span: span.clone(),
+                }));
+            }
+
+            Ok(buffer.to_vec())
+        };
+        let limit = self.context.max_loop_iterations();
+        let exceeded = |iterations: String| {
+            self.diagnostics.report(Error::LoopIterationsExceeded {
+                limit,
+                iterations,
+                span: span.clone(),
+            })
+        };
+
+        match statement.source() {
+            CstLoopSource::Range { start, end } => {
+                // both bounds are evaluated to report errors in each of them
+                let start = evaluate(start, scope);
+                let end = evaluate(end, scope);
+                let (start, end) = (integer_from_bytes(&start?), integer_from_bytes(&end?));
+
+                let iterations = if end > start {
+                    &end - &start
+                } else {
+                    BigInt::ZERO
+                };
+                if iterations > BigInt::from(limit) {
+                    return Err(exceeded(iterations.to_string()));
                 }
-                CstAtom::Function { name, params } => {
-                    self.build_function_into(scope_id, scope, name.clone(), params, buffer)?
+
+                let mut values = Vec::new();
+                let mut value = start;
+                while value < end {
+                    values.push(ByteBuffer::from(value.to_bytes_be().1));
+                    value += 1;
+                }
+
+                Ok(values)
+            }
+            CstLoopSource::Bytes { value } => {
+                let bytes = evaluate(value, scope)?;
+                if bytes.len() > limit {
+                    return Err(exceeded(bytes.len().to_string()));
+                }
+
+                Ok(bytes.into_iter().map(|byte| ByteBuffer::from(vec![byte])).collect())
+            }
+        }
+    }
+
+    /// Emits value of [expression], expressions depending on labels are back-patched later
+    fn build_expression_into(
+        &self,
+        scope_id: HexoId,
+        scope: &mut CompilationScope,
+        expression: &CstExpression,
+        format: Option<IntegerFormat>,
+        span: &Span,
+        buffer: &mut ByteBuffer,
+    ) -> Result<(), Poisoned> {
+        match self.evaluate_expression(scope_id, scope, expression)? {
+            Expression::Value(value) => {
+                let bytes = encode_integer(&value, format, span)
+                    .map_err(|error| self.diagnostics.report(error))?;
+                match format {
+                    Some(format) => push_integer(&bytes, format, buffer),
+                    None => bytes.into_iter().for_each(|byte| buffer.push_byte(byte)),
                 }
             }
+            expression => {
+                // label offsets are only known after the whole output is built, so width must be fixed
+                let format = format.unwrap_or(IntegerFormat::U32_BE);
+                let marker = LabelMarker::Reference {
+                    expression,
+                    format,
+                    span: span.clone(),
+                };
+                self.push_label_marker(marker, format.width(), buffer);
+            }
         }
 
         Ok(())
     }
 
+    fn evaluate_expression(
+        &self,
+        scope_id: HexoId,
+        scope: &mut CompilationScope,
+        expression: &CstExpression,
+    ) -> Result<Expression, Poisoned> {
+        match expression {
+            CstExpression::Number { value, .. } => Ok(Expression::Value(value.clone())),
+            CstExpression::Label { name, span } => Ok(Expression::Label {
+                name: name.clone(),
+                span: span.clone(),
+            }),
+            CstExpression::Atom { atom, span } => {
+                let mut atom_buffer = ByteBuffer::default();
+                self.build_atom_into(scope_id, scope, atom, &mut atom_buffer)?;
+
+                if !atom_buffer.markers().is_empty() {
+                    return Err(self.diagnostics.report(Error::LabelInExpressionOperand {
+                        span: span.clone(),
+                    }));
+                }
+
+                Ok(Expression::Value(integer_from_bytes(&atom_buffer.to_vec())))
+            }
+            CstExpression::Binary {
+                operator,
+                left,
+                right,
+                span,
+            } => {
+                // both operands are evaluated to report errors in each of them
+                let left = self.evaluate_expression(scope_id, scope, left);
+                let right = self.evaluate_expression(scope_id, scope, right);
+
+                Expression::binary(*operator, left?, right?, span)
+                    .map_err(|error| self.diagnostics.report(error))
+            }
+        }
+    }
+
+    fn push_label_marker(&self, marker: LabelMarker, width: usize, buffer: &mut ByteBuffer) {
+        let id = HexoId::next();
+        self.label_markers.borrow_mut().insert(id, marker);
+        buffer.push_marker(id, width);
+    }
+
+    /// Second pass over the output, back-patches label references once all label offsets are known
+    fn resolve_labels(&self, buffer: &mut ByteBuffer) {
+        let label_markers = self.label_markers.borrow();
+        let mut offsets = HashMap::new();
+
+        for marker in buffer.markers() {
+            if let Some(LabelMarker::Definition { name, span }) = label_markers.get(&marker.id) {
+                if offsets.insert(name.clone(), marker.offset).is_some() {
+                    self.diagnostics.report(Error::DuplicateLabel {
+                        name: name.clone(),
+                        span: span.clone(),
+                    });
+                }
+            }
+        }
+
+        let references: Vec<ByteMarker> = buffer.markers().clone();
+        for marker in references {
+            let Some(LabelMarker::Reference {
+                expression,
+                format,
+                span,
+            }) = label_markers.get(&marker.id)
+            else {
+                continue;
+            };
+
+            let bytes = expression
+                .evaluate(&offsets)
+                .and_then(|value| encode_integer(&value, Some(*format), span));
+
+            match bytes {
+                Ok(bytes) => buffer.patch(&marker, &bytes),
+                Err(error) => {
+                    self.diagnostics.report(error);
+                }
+            }
+        }
+
+        self.resolve_output_ranges(buffer, &offsets);
+    }
+
+    /// Computes results of native functions over output ranges, the ones whose range contains
+    /// results of others are computed last
+    fn resolve_output_ranges(&self, buffer: &mut ByteBuffer, offsets: &HashMap<String, usize>) {
+        let label_markers = self.label_markers.borrow();
+        let mut pending = Vec::new();
+
+        for marker in buffer.markers() {
+            let Some(LabelMarker::OutputRange(output_range)) = label_markers.get(&marker.id) else {
+                continue;
+            };
+
+            match self.evaluate_output_range(output_range, buffer.len(), offsets) {
+                Ok(range) => pending.push((*marker, range)),
+                Err(error) => {
+                    self.diagnostics.report(error);
+                }
+            }
+        }
+
+        while !pending.is_empty() {
+            let ready = pending.iter().position(|(_, range)| {
+                pending.iter().all(|(other, _)| {
+                    other.offset + other.width <= range.start || other.offset >= range.end
+                })
+            });
+
+            let Some(index) = ready else {
+                for (marker, _) in pending {
+                    if let Some(LabelMarker::OutputRange(output_range)) =
+                        label_markers.get(&marker.id)
+                    {
+                        self.diagnostics.report(Error::OutputRangeCycle {
+                            name: output_range.function.signature().name().to_string(),
+                            span: output_range.span.clone(),
+                        });
+                    }
+                }
+                return;
+            };
+
+            let (marker, range) = pending.remove(index);
+            let Some(LabelMarker::OutputRange(output_range)) = label_markers.get(&marker.id) else {
+                continue;
+            };
+            let OutputRange {
+                function,
+                range_param,
+                params,
+                span,
+                ..
+            } = output_range.as_ref();
+
+            let mut params = params.clone();
+            params.insert(
+                range_param.clone(),
+                ByteBuffer::from(buffer.to_vec()[range].to_vec()),
+            );
+
+            // failure is already reported
+            let Ok(result) = self.execute_native_function(function, &params, span) else {
+                continue;
+            };
+
+            if result.len() != marker.width {
+                self.diagnostics.report(Error::OutputRangeResultLength {
+                    name: function.signature().name().to_string(),
+                    expected: marker.width,
+                    actual: result.len(),
+                    span: span.clone(),
+                });
+                continue;
+            }
+
+            buffer.patch(&marker, &result.to_vec());
+        }
+    }
+
+    fn evaluate_output_range(
+        &self,
+        output_range: &OutputRange,
+        length: usize,
+        offsets: &HashMap<String, usize>,
+    ) -> Result<Range<usize>, Error> {
+        let from = output_range.from.evaluate(offsets)?;
+        let to = output_range.to.evaluate(offsets)?;
+
+        match (usize::try_from(&from), usize::try_from(&to)) {
+            (Ok(start), Ok(end)) if start <= end && end <= length => Ok(start..end),
+            _ => Err(Error::OutputRangeOutOfBounds {
+                from: from.to_string(),
+                to: to.to_string(),
+                length,
+                span: output_range.span.clone(),
+            }),
+        }
+    }
+
+    fn execute_native_function(
+        &self,
+        native_function: &NativeFunction,
+        params: &HashMap<String, ByteBuffer>,
+        span: &Span,
+    ) -> Result<ByteBuffer, Poisoned> {
+        let report = |error| {
+            self.diagnostics.report(Error::NativeFunctionExecution {
+                error,
+                span: span.clone(),
+            })
+        };
+
+        let arguments = native_function
+            .signature()
+            .bind_arguments(params, span.path())
+            .map_err(report)?;
+        // functions producing output from their arguments check the limit before building it
+        let limit = self.context.max_output_size();
+        let result = (native_function.executor())(arguments, self.parent).map_err(report)?;
+        if result.len() > limit {
+            return Err(report(NativeFunctionError::OutputSizeExceeded {
+                function_name: native_function.signature().name().to_string(),
+                limit,
+            }));
+        }
+
+        Ok(result)
+    }
+
+    /// Reserves room for result of [native_function] over the output between `from` and `to`
+    /// labels, it is computed by [Self::resolve_output_ranges] once the output is complete
+    fn build_output_range_function_into(
+        &self,
+        native_function: &NativeFunction,
+        range_param: &str,
+        params: &mut HashMap<String, ByteBuffer>,
+        span: &Span,
+        buffer: &mut ByteBuffer,
+    ) -> Result<(), Poisoned> {
+        let name = native_function.signature().name().to_string();
+        let from = self.take_output_range_bound(&name, params, "from", span);
+        let to = self.take_output_range_bound(&name, params, "to", span);
+        let (from, to) = (from?, to?);
+
+        if params.values().any(|param| !param.markers().is_empty()) {
+            return Err(self.diagnostics.report(Error::LabelInNativeFunctionArgument {
+                name,
+                span: span.clone(),
+            }));
+        }
+
+        // result length doesn't depend on the range, so it can be learned from an empty one
+        params.insert(range_param.to_string(), ByteBuffer::default());
+        let width = self.execute_native_function(native_function, params, span)?.len();
+
+        let marker = LabelMarker::OutputRange(Box::new(OutputRange {
+            function: native_function.clone(),
+            range_param: range_param.to_string(),
+            params: params.clone(),
+            from,
+            to,
+            span: span.clone(),
+        }));
+        self.push_label_marker(marker, width, buffer);
+
+        Ok(())
+    }
+
+    /// Takes bound of the output range out of [params], bound is a label expression or an offset
+    fn take_output_range_bound(
+        &self,
+        function: &str,
+        params: &mut HashMap<String, ByteBuffer>,
+        name: &str,
+        span: &Span,
+    ) -> Result<Expression, Poisoned> {
+        let Some(bound) = params.remove(name) else {
+            return Err(self.diagnostics.report(Error::MissingArgument {
+                function: function.to_string(),
+                name: name.to_string(),
+                span: span.clone(),
+            }));
+        };
+
+        match bound.markers().as_slice() {
+            [] => Ok(Expression::Value(integer_from_bytes(&bound.to_vec()))),
+            [marker] if marker.offset == 0 && marker.width == bound.len() => {
+                let mut label_markers = self.label_markers.borrow_mut();
+                match label_markers.remove(&marker.id) {
+                    Some(LabelMarker::Reference { expression, .. }) => return Ok(expression),
+                    // label definitions from function bodies and results of nested output ranges
+                    Some(other) => {
+                        label_markers.insert(marker.id, other);
+                    }
+                    None => {}
+                }
+                drop(label_markers);
+
+                Err(self.diagnostics.report(Error::InvalidOutputRangeBound {
+                    function: function.to_string(),
+                    name: name.to_string(),
+                    span: span.clone(),
+                }))
+            }
+            _ => Err(self.diagnostics.report(Error::LabelInNativeFunctionArgument {
+                name: function.to_string(),
+                span: span.clone(),
+            })),
+        }
+    }
+
     fn build_function_into(
         &self,
         scope_id: HexoId,
         scope: &mut CompilationScope,
         function_name: String,
         params: &Vec<CstActualParameter>,
+        span: &Span,
         buffer: &mut ByteBuffer,
-    ) -> Result<(), Error> {
-        let native_function = scope.get_native_function(function_name.as_str());
+    ) -> Result<(), Poisoned> {
+        let native_function = self.context.native_functions().find(function_name.clone());
         if let Some(native_function) = native_function {
-            if self.safe_mode && !native_function.signature().is_safe() {
-                return Err(
-                    Error::NativeFunctionIsUnsafe {
-                        name: native_function.signature().name().to_string()
-                    }
-                );
+            if self.context.safe_mode()
+                && !self.context.is_safe_function(native_function.signature())
+            {
+                return Err(self.diagnostics.report(Error::NativeFunctionIsUnsafe {
+                    name: native_function.signature().name().to_string(),
+                    span: span.clone(),
+                }));
             }
 
-            let executor = native_function.executor();
-            let mut params_buffer = HashMap::new();
+            let mut params_buffer = self.build_params(scope_id, scope, params)?;
 
-            for param in params {
-                let mut param_buffer = ByteBuffer::default();
-                self.build_bytes_into(scope_id, scope, param.value(), &mut param_buffer)?;
+            if let Some(range_param) = native_function.signature().output_range() {
+                if params_buffer.contains_key("from") || params_buffer.contains_key("to") {
+                    let range_param = range_param.to_string();
+                    return self.build_output_range_function_into(
+                        native_function,
+                        &range_param,
+                        &mut params_buffer,
+                        span,
+                        buffer,
+                    );
+                }
+            }
 
-                params_buffer.insert(param.name().to_string(), param_buffer);
+            if let Some(offset_param) = native_function.signature().output_offset() {
+                if !params_buffer.contains_key(offset_param) {
+                    if self.deciding_condition.get() {
+                        return Err(self.diagnostics.report(Error::OutputOffsetInCondition {
+                            name: function_name,
+                            span: span.clone(),
+                        }));
+                    }
+                    let offset = BigInt::from(buffer.len()).to_bytes_be().1;
+                    params_buffer.insert(offset_param.to_string(), ByteBuffer::from(offset));
+                }
             }
 
-            let arguments = NativeFunctionArguments::new(&params_buffer);
-            executor(arguments, self.parent)
-                .map(|bb| buffer.push_byte_buffer(&bb))
-                .map_err(Error::NativeFunctionExecution)?;
+            // native functions operate on plain bytes, label markers would be lost
+            if params_buffer.values().any(|param| !param.markers().is_empty()) {
+                return Err(self.diagnostics.report(Error::LabelInNativeFunctionArgument {
+                    name: function_name,
+                    span: span.clone(),
+                }));
+            }
+
+            let result = self.execute_native_function(native_function, &params_buffer, span)?;
+            buffer.push_byte_buffer(&result);
 
             return Ok(());
         }
 
-        let binding = scope.clone();
-        let function_binding = binding
+        let params_result = self.build_params(scope_id, scope, params);
+
+        let function_binding = scope
             .get_local_function(scope_id, &function_name)
-            .ok_or(Error::UnresolvedFunction {
-                name: function_name.clone(),
+            .cloned()
+            .ok_or_else(|| {
+                self.diagnostics.report(Error::UnresolvedFunction {
+                    name: function_name.clone(),
+                    span: span.clone(),
+                })
             })?;
+        let params_buffer = params_result?;
 
-        for param in params {
-            let mut param_buffer = ByteBuffer::default();
-            self.build_bytes_into(scope_id, scope, param.value(), &mut param_buffer).unwrap();
+        if self.call_stack.borrow().len() >= self.context.max_call_depth() {
+            let mut call_stack = self.call_stack.borrow().clone();
+            call_stack.push(function_name);
+            return Err(self.diagnostics.report(Error::CallDepthExceeded {
+                limit: self.context.max_call_depth(),
+                call_stack,
+                span: span.clone(),
+            }));
+        }
+
+        // every call gets its own activation scope, so constants derived from parameters
+        // are evaluated per call and don't leak into other calls of the same function
+        let activation_id = HexoId::next();
+        scope.bind_parents(activation_id, vec![function_binding.parent]);
+        if let Err(poisoned) =
+            self.bind_arguments(activation_id, scope, &function_binding, params_buffer, span)
+        {
+            scope.remove_local_scope(activation_id);
+            return Err(poisoned);
+        }
+
+        self.call_stack.borrow_mut().push(function_name);
+        self.build_scope_into(activation_id, &function_binding.statement.as_ref(), scope);
+
+        let mut result = Ok(());
+        for emit in function_binding.statement.emits() {
+            let emit_result = self.build_bytes_into(activation_id, scope, emit.atoms(), buffer);
+            result = result.and(emit_result);
+        }
 
+        self.call_stack.borrow_mut().pop();
+        scope.remove_local_scope(activation_id);
+
+        result
+    }
+
+    /// Binds [arguments] to parameters declared by [function], omitted ones get their defaults
+    fn bind_arguments(
+        &self,
+        activation_id: HexoId,
+        scope: &mut CompilationScope,
+        function: &FunctionBinding,
+        mut arguments: HashMap<String, ByteBuffer>,
+        span: &Span,
+    ) -> Result<(), Poisoned> {
+        let bind = |scope: &mut CompilationScope, name: String, byte_buffer: ByteBuffer| {
             scope.bind_local_constant(
-                function_binding.identifier,
+                activation_id,
                 ConstantBinding {
-                    name: param.name().to_string(),
-                    byte_buffer: param_buffer,
+                    name,
+                    byte_buffer,
+                    poisoned: false,
                 },
-            );
+            )
+        };
+
+        let Some(params) = function.statement.params() else {
+            for (name, argument) in arguments {
+                bind(scope, name, argument);
+            }
+            return Ok(());
+        };
+
+        let mut result = Ok(());
+        for (position, param) in params.iter().enumerate() {
+            let positional = arguments.remove(&position.to_string());
+            let named = arguments.remove(param.name());
+
+            let argument = match (positional, named) {
+                (Some(argument), None) | (None, Some(argument)) => argument,
+                (Some(_), Some(_)) => {
+                    result = Err(self.diagnostics.report(Error::DuplicateArgument {
+                        function: function.name.clone(),
+                        name: param.name().to_string(),
+                        span: span.clone(),
+                    }));
+                    continue;
+                }
+                (None, None) => {
+                    let Some(default) = param.default() else {
+                        result = Err(self.diagnostics.report(Error::MissingArgument {
+                            function: function.name.clone(),
+                            name: param.name().to_string(),
+                            span: span.clone(),
+                        }));
+                        continue;
+                    };
+
+                    // defaults are evaluated in the activation scope, so they can use preceding parameters
+                    let mut argument = ByteBuffer::default();
+                    let default_result =
+                        self.build_bytes_into(activation_id, scope, default, &mut argument);
+                    result = result.and(default_result);
+                    argument
+                }
+            };
+
+            bind(scope, param.name().to_string(), argument);
         }
 
-        for emit in &function_binding.emits {
-            self.build_bytes_into(function_binding.identifier, scope, emit.atoms(), buffer)
-                .unwrap();
+        let mut unknown: Vec<String> = arguments.into_keys().collect();
+        unknown.sort();
+        for name in unknown {
+            result = Err(self.diagnostics.report(Error::UnknownArgument {
+                function: function.name.clone(),
+                name,
+                span: span.clone(),
+            }));
         }
 
-        Ok(())
+        result
+    }
+
+    /// Evaluates all [params], so errors in each of them get reported
+    fn build_params(
+        &self,
+        scope_id: HexoId,
+        scope: &mut CompilationScope,
+        params: &Vec<CstActualParameter>,
+    ) -> Result<HashMap<String, ByteBuffer>, Poisoned> {
+        let mut params_buffer = HashMap::new();
+        let mut result = Ok(());
+
+        for param in params {
+            let mut param_buffer = ByteBuffer::default();
+            let param_result = self.with_own_offset(|| {
+                self.build_bytes_into(scope_id, scope, param.value(), &mut param_buffer)
+            });
+            result = result.and(param_result);
+
+            params_buffer.insert(param.name().to_string(), param_buffer);
+        }
+
+        result.map(|_| params_buffer)
     }
 
     fn build_constant_into(
+        &self,
         scope_id: HexoId,
         scope: &CompilationScope,
         name: &String,
+        span: &Span,
         buffer: &mut ByteBuffer,
-    ) -> Result<(), Error> {
-        let constant_binding = scope
-            .get_local_constant(scope_id, name)
-            .ok_or(Error::UnresolvedConstant { name: name.clone() })?;
+    ) -> Result<(), Poisoned> {
+        self.mark_definition_used(name);
+        let constant_binding = scope.get_local_constant(scope_id, name).ok_or_else(|| {
+            self.diagnostics.report(Error::UnresolvedConstant {
+                name: name.clone(),
+                span: span.clone(),
+            })
+        })?;
+
+        // constant evaluation has failed and was reported, everything using it is poisoned too
+        if constant_binding.poisoned {
+            return Err(Poisoned);
+        }
 
         buffer.push_byte_buffer(&constant_binding.byte_buffer);
 
         Ok(())
     }
 
-    fn build_scope(
-        &self,
-        scope_id: HexoId,
-        cst: &CstFunctionStatement,
-    ) -> Result<CompilationScope, Error> {
-        let mut root_scope = CompilationScope::default();
+    /// Binds [definitions] before any statement of the file, so constants can't shadow them
+    fn build_scope_definitions_into(&self, scope_id: HexoId, scope: &mut CompilationScope) {
+        for definition in &self.definitions {
+            let mut buff = ByteBuffer::default();
+            let result = self.build_bytes_into(scope_id, scope, definition.atoms(), &mut buff);
+            scope.bind_local_constant(
+                scope_id,
+                ConstantBinding {
+                    name: definition.name().to_string(),
+                    byte_buffer: buff,
+                    poisoned: result.is_err(),
+                },
+            )
+        }
+    }
 
-        self.build_scope_into(scope_id, &cst, &mut root_scope)?;
+    fn mark_definition_used(&self, name: &str) {
+        if self.definitions.iter().any(|definition| definition.name() == name) {
+            self.used_definitions.borrow_mut().insert(name.to_string());
+        }
+    }
 
-        Ok(root_scope)
+    /// Definitions neither overriding nor used are most likely misspelled, they would be ignored
+    fn warn_unused_definitions(&self) {
+        let used_definitions = self.used_definitions.borrow();
+        for definition in &self.definitions {
+            if !used_definitions.contains(definition.name()) {
+                self.diagnostics.warn(Warning::UnusedDefinition {
+                    name: definition.name().to_string(),
+                    span: definition.span().clone(),
+                });
+            }
+        }
+    }
+
+    fn is_overridden(&self, scope_id: HexoId, constant: &CstConstantStatement) -> bool {
+        scope_id == self.root_scope_id
+            && self
+                .definitions
+                .iter()
+                .any(|definition| definition.name() == constant.name())
     }
 
     fn build_scope_into(
@@ -167,31 +975,130 @@ impl RstCompiler<'_> {
         scope_id: HexoId,
         cst: &&CstFunctionStatement,
         root_scope: &mut CompilationScope,
-    ) -> Result<(), Error> {
-        self.build_scope_constants_into(scope_id, cst, root_scope)?;
-        self.build_scope_functions_into(scope_id, cst, root_scope)?;
-        Ok(())
+    ) {
+        self.build_scope_includes_into(scope_id, cst, root_scope);
+        self.build_scope_functions_into(scope_id, cst, root_scope);
+        self.build_scope_declarations_into(scope_id, cst, root_scope);
     }
 
-    fn build_scope_constants_into(
+    fn build_scope_includes_into(
         &self,
         scope_id: HexoId,
         cst: &&CstFunctionStatement,
         scope: &mut CompilationScope,
-    ) -> Result<(), Error> {
-        for constant in cst.constants() {
-            let mut buff = ByteBuffer::default();
-            self.build_bytes_into(scope_id, scope, constant.atoms(), &mut buff)?;
-            scope.bind_local_constant(
-                scope_id,
-                ConstantBinding {
+    ) {
+        for include in cst.includes() {
+            if let Some(included) = self.load_include(include) {
+                if !included.main().emits().is_empty() {
+                    self.diagnostics.warn(Warning::IncludedEmitsIgnored {
+                        path: included.path().to_path_buf(),
+                        span: include.span().clone(),
+                    });
+                }
+
+                self.include_chain
+                    .borrow_mut()
+                    .push(Self::canonical_path(included.path()));
+                self.build_scope_into(scope_id, &included.main(), scope);
+                self.include_chain.borrow_mut().pop();
+            }
+        }
+    }
+
+    /// Parses the file referenced by [include], returns [None] if it can't be included
+    fn load_include(&self, include: &CstIncludeStatement) -> Option<CstFile> {
+        let chain = self.include_chain.borrow().clone();
+        let including_file = chain.last().map(PathBuf::as_path).unwrap_or(Path::new(""));
+        let path = including_file
+            .parent()
+            .unwrap_or(Path::new(""))
+            .join(include.path());
+
+        let canonical_path = Self::canonical_path(&path);
+        if chain.contains(&canonical_path) {
+            let mut cycle = chain;
+            cycle.push(canonical_path);
+            self.diagnostics.report(Error::IncludeCycle {
+                chain: cycle,
+                span: include.span().clone(),
+            });
+            return None;
+        }
+
+        self.parent
+            .compile_cst(self.source.include(&path).as_ref())
+            .map_err(|error| {
+                self.diagnostics.report(Error::IncludeFailed {
+                    path: path,
+                    error: Box::new(error),
+                    span: include.span().clone(),
+                })
+            })
+            .ok()
+    }
+
+    /// Runs [build] into a buffer of its own, like an argument or a constant, offsets of the
+    /// output functions see are counted from the start of that buffer even in conditions
+    fn with_own_offset<T>(&self, build: impl FnOnce() -> T) -> T {
+        let deciding_condition = self.deciding_condition.replace(false);
+        let result = build();
+        self.deciding_condition.set(deciding_condition);
+        result
+    }
+
+    fn canonical_path(path: &Path) -> PathBuf {
+        path.canonicalize().unwrap_or(path.to_path_buf())
+    }
+
+    /// Evaluates constants and conditionals in source order, so each of them sees only the
+    /// declarations written before it
+    fn build_scope_declarations_into(
+        &self,
+        scope_id: HexoId,
+        cst: &&CstFunctionStatement,
+        scope: &mut CompilationScope,
+    ) {
+        for declaration in cst.declarations() {
+            match declaration {
+                CstDeclaration::Constant(constant) => {
+                    self.build_scope_constant_into(scope_id, constant, scope)
+                }
+                CstDeclaration::Conditional(conditional) => {
+                    self.build_scope_conditional_into(scope_id, conditional, scope)
+                }
+            }
+        }
+    }
+
+    fn build_scope_constant_into(
+        &self,
+        scope_id: HexoId,
+        constant: &CstConstantStatement,
+        scope: &mut CompilationScope,
+    ) {
+        if self.is_overridden(scope_id, constant) {
+            self.mark_definition_used(constant.name());
+            if !constant.is_overridable() {
+                self.diagnostics.warn(Warning::ConstantOverridden {
                     name: constant.name().to_string(),
-                    byte_buffer: buff,
-                },
-            )
+                    span: constant.span().clone(),
+                });
+            }
+            return;
         }
 
-        Ok(())
+        let mut buff = ByteBuffer::default();
+        let result = self.with_own_offset(|| {
+            self.build_bytes_into(scope_id, scope, constant.atoms(), &mut buff)
+        });
+        scope.bind_local_constant(
+            scope_id,
+            ConstantBinding {
+                name: constant.name().to_string(),
+                byte_buffer: buff,
+                poisoned: result.is_err(),
+            },
+        )
     }
 
     fn build_scope_functions_into(
@@ -199,23 +1106,134 @@ impl RstCompiler<'_> {
         scope_id: HexoId,
         cst: &&CstFunctionStatement,
         root_scope: &mut CompilationScope,
-    ) -> Result<(), Error> {
+    ) {
         for function in cst.functions() {
-            let inner_function_scope_id = HexoId::next();
+            // function body is evaluated in a fresh scope on every call
             root_scope.bind_local_function(
                 scope_id,
                 FunctionBinding {
-                    identifier: inner_function_scope_id,
+                    parent: scope_id,
                     name: function.name().to_string(),
-                    emits: function.emits().clone(),
+                    statement: Rc::new(function.clone()),
                 },
             );
+        }
+    }
+
+    /// Decides which branch of [conditional] is taken, statements of the taken branch join
+    /// [scope_id] as if they were written in place of the conditional
+    fn build_scope_conditional_into(
+        &self,
+        scope_id: HexoId,
+        conditional: &CstConditionalStatement,
+        scope: &mut CompilationScope,
+    ) {
+        let mut binding = ConditionalBinding {
+            id: conditional.id(),
+            branch: None,
+            poisoned: false,
+        };
 
-            self.build_scope_into(inner_function_scope_id, &function, root_scope)?;
+        for (index, branch) in conditional.branches().iter().enumerate() {
+            // conditions are decided before any output, so functions can't depend on its offset
+            let holds = match branch.condition() {
+                Some(condition) => {
+                    let deciding_condition = self.deciding_condition.replace(true);
+                    let holds = self.evaluate_condition(
+                        scope_id,
+                        scope,
+                        condition,
+                        branch.span(),
+                        &mut ByteBuffer::default(),
+                    );
+                    self.deciding_condition.set(deciding_condition);
+                    holds
+                }
+                None => Ok(true),
+            };
 
-            root_scope.bind_parents(inner_function_scope_id, vec![scope_id]);
+            match holds {
+                Ok(true) => {
+                    binding.branch = Some(index);
+                    break;
+                }
+                Ok(false) => {}
+                Err(Poisoned) => {
+                    binding.poisoned = true;
+                    break;
+                }
+            }
         }
 
-        Ok(())
+        let taken = binding.branch;
+        scope.bind_local_conditional(scope_id, binding);
+        if let Some(index) = taken {
+            self.build_scope_into(scope_id, &conditional.branches()[index].body(), scope);
+        }
+    }
+
+    /// Evaluates [condition] as if its values were emitted at the end of [buffer], which is left
+    /// unchanged
+    fn evaluate_condition(
+        &self,
+        scope_id: HexoId,
+        scope: &mut CompilationScope,
+        condition: &CstCondition,
+        span: &Span,
+        buffer: &mut ByteBuffer,
+    ) -> Result<bool, Poisoned> {
+        let mut evaluate = |atoms, scope: &mut CompilationScope| {
+            self.evaluate_condition_value(scope_id, scope, atoms, span, buffer)
+        };
+
+        match condition {
+            CstCondition::Defined { name } => {
+                self.mark_definition_used(name);
+                Ok(scope.get_local_constant(scope_id, name).is_some())
+            }
+            CstCondition::Equal { left, right } => {
+                // both sides are evaluated to report errors in each of them
+                let left = evaluate(left, scope);
+                let right = evaluate(right, scope);
+
+                Ok(left? == right?)
+            }
+            CstCondition::Less { left, right } => {
+                let left = evaluate(left, scope);
+                let right = evaluate(right, scope);
+
+                Ok(integer_from_bytes(&left?) < integer_from_bytes(&right?))
+            }
+            CstCondition::NonZero { value } => {
+                let value = evaluate(value, scope)?;
+                Ok(value.iter().any(|byte| *byte != 0))
+            }
+            CstCondition::Not(condition) => self
+                .evaluate_condition(scope_id, scope, condition, span, buffer)
+                .map(|holds| !holds),
+        }
+    }
+
+    fn evaluate_condition_value(
+        &self,
+        scope_id: HexoId,
+        scope: &mut CompilationScope,
+        atoms: &CstAtomVec,
+        span: &Span,
+        buffer: &mut ByteBuffer,
+    ) -> Result<Vec<u8>, Poisoned> {
+        // value is built in place, so functions depending on output offset see the right one
+        let start = buffer.len();
+        let result = self.build_bytes_into(scope_id, scope, atoms, buffer);
+        let buffer = buffer.split_off(start);
+        result?;
+
+        if !buffer.markers().is_empty() {
+            return Err(self.diagnostics.report(Error::LabelInCondition {
+                span: span.clone(),
+            }));
+        }
+
+        Ok(buffer.to_vec())
     }
 }
@@ -0,0 +1,49 @@
+use std::cell::RefCell;
+
+use crate::compiler::rst::{Error, Warning};
+use crate::util::logger;
+
+/// Marker of a failed evaluation, its cause is already reported to [Diagnostics]
+#[derive(Debug)]
+pub(crate) struct Poisoned;
+
+/// Collects errors and warnings of a single RST compilation, so all of them can be reported at once
+#[derive(Debug, Default)]
+pub(crate) struct Diagnostics {
+    errors: RefCell<Vec<Error>>,
+    warnings: RefCell<Vec<Warning>>,
+}
+
+impl Diagnostics {
+    /// Records [error] unless the same error at the same location was already reported
+    pub(crate) fn report(&self, error: Error) -> Poisoned {
+        let mut errors = self.errors.borrow_mut();
+        let is_duplicate = errors
+            .iter()
+            .any(|e| e.span() == error.span() && e.to_string() == error.to_string());
+
+        if !is_duplicate {
+            errors.push(error);
+        }
+
+        Poisoned
+    }
+
+    /// Records [warning] and logs it right away, compilation goes on
+    pub(crate) fn warn(&self, warning: Warning) {
+        logger::warning!("{}", warning);
+        self.warnings.borrow_mut().push(warning);
+    }
+
+    pub(crate) fn take_warnings(&self) -> Vec<Warning> {
+        self.warnings.take()
+    }
+
+    pub(crate) fn has_errors(&self) -> bool {
+        !self.errors.borrow().is_empty()
+    }
+
+    pub(crate) fn into_errors(self) -> Vec<Error> {
+        self.errors.into_inner()
+    }
+}
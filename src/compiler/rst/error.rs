@@ -1,26 +1,299 @@
+use std::path::PathBuf;
+
+use crate::compiler::span::Span;
+
 #[derive(Debug)]
-pub(crate) enum Error {
-    UnresolvedConstant { name: String },
-    UnresolvedFunction { name: String },
-    NativeFunctionExecution(crate::compiler::native_fn::Error),
-    NativeFunctionIsUnsafe { name: String },
+pub enum Error {
+    UnresolvedConstant {
+        name: String,
+        span: Span,
+    },
+    UnresolvedFunction {
+        name: String,
+        span: Span,
+    },
+    NativeFunctionExecution {
+        error: crate::compiler::native_fn::Error,
+        span: Span,
+    },
+    NativeFunctionIsUnsafe {
+        name: String,
+        span: Span,
+    },
+    IncludeFailed {
+        path: PathBuf,
+        error: Box<crate::compiler::Error>,
+        span: Span,
+    },
+    IncludeCycle {
+        chain: Vec<PathBuf>,
+        span: Span,
+    },
+    UnresolvedLabel {
+        name: String,
+        span: Span,
+    },
+    DuplicateLabel {
+        name: String,
+        span: Span,
+    },
+    IntegerOverflow {
+        value: String,
+        format: String,
+        span: Span,
+    },
+    DivisionByZero {
+        span: Span,
+    },
+    InvalidShift {
+        amount: String,
+        span: Span,
+    },
+    LabelInExpressionOperand {
+        span: Span,
+    },
+    LabelInNativeFunctionArgument {
+        name: String,
+        span: Span,
+    },
+    MissingArgument {
+        function: String,
+        name: String,
+        span: Span,
+    },
+    UnknownArgument {
+        function: String,
+        name: String,
+        span: Span,
+    },
+    DuplicateArgument {
+        function: String,
+        name: String,
+        span: Span,
+    },
+    OutputRangeOutOfBounds {
+        from: String,
+        to: String,
+        length: usize,
+        span: Span,
+    },
+    OutputRangeCycle {
+        name: String,
+        span: Span,
+    },
+    OutputRangeResultLength {
+        name: String,
+        expected: usize,
+        actual: usize,
+        span: Span,
+    },
+    /// Output range bound is neither an offset nor a plain label reference
+    InvalidOutputRangeBound {
+        function: String,
+        name: String,
+        span: Span,
+    },
+    /// Conditions are decided before the output is laid out, so label offsets are unknown
+    LabelInCondition {
+        span: Span,
+    },
+    /// Same as [Error::LabelInCondition], for native functions like `offset` and `align`
+    OutputOffsetInCondition {
+        name: String,
+        span: Span,
+    },
+    LabelInLoopSource {
+        span: Span,
+    },
+    LoopIterationsExceeded {
+        limit: usize,
+        iterations: String,
+        span: Span,
+    },
+    AssertionFailed {
+        message: Option<String>,
+        span: Span,
+    },
+    CallDepthExceeded {
+        limit: usize,
+        /// Names of called functions, outermost first
+        call_stack: Vec<String>,
+        span: Span,
+    },
+}
+
+impl Error {
+    /// Location of the source code that caused the error
+    pub fn span(&self) -> Option<&Span> {
+        match self {
+            Error::UnresolvedConstant { span, .. } => Some(span),
+            Error::UnresolvedFunction { span, .. } => Some(span),
+            Error::NativeFunctionExecution { span, .. } => Some(span),
+            Error::NativeFunctionIsUnsafe { span, .. } => Some(span),
+            Error::IncludeFailed { span, .. } => Some(span),
+            Error::IncludeCycle { span, .. } => Some(span),
+            Error::UnresolvedLabel { span, .. } => Some(span),
+            Error::DuplicateLabel { span, .. } => Some(span),
+            Error::IntegerOverflow { span, .. } => Some(span),
+            Error::DivisionByZero { span } => Some(span),
+            Error::InvalidShift { span, .. } => Some(span),
+            Error::LabelInExpressionOperand { span } => Some(span),
+            Error::MissingArgument { span, .. } => Some(span),
+            Error::UnknownArgument { span, .. } => Some(span),
+            Error::DuplicateArgument { span, .. } => Some(span),
+            Error::OutputRangeOutOfBounds { span, .. } => Some(span),
+            Error::OutputRangeCycle { span, .. } => Some(span),
+            Error::OutputRangeResultLength { span, .. } => Some(span),
+            Error::InvalidOutputRangeBound { span, .. } => Some(span),
+            Error::LabelInCondition { span } => Some(span),
+            Error::OutputOffsetInCondition { span, .. } => Some(span),
+            Error::LabelInLoopSource { span } => Some(span),
+            Error::LoopIterationsExceeded { span, .. } => Some(span),
+            Error::AssertionFailed { span, .. } => Some(span),
+            Error::CallDepthExceeded { span, .. } => Some(span),
+            Error::LabelInNativeFunctionArgument { span, .. } => Some(span),
+        }
+    }
 }
 
 impl std::fmt::Display for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Error::UnresolvedConstant { name } => {
+            Error::UnresolvedConstant { name, .. } => {
                 write!(f, "Unresolved constant: {}", name)
             }
-            Error::UnresolvedFunction { name } => {
+            Error::UnresolvedFunction { name, .. } => {
                 write!(f, "Unresolved function: {}", name)
             }
-            Error::NativeFunctionExecution(e) => {
-                write!(f, "Native function execution error: {}", e)
+            Error::NativeFunctionExecution { error, .. } => {
+                write!(f, "Native function execution error: {}", error)
             }
-            Error::NativeFunctionIsUnsafe { name } => {
+            Error::NativeFunctionIsUnsafe { name, .. } => {
                 write!(f, "Native function is unsafe: {}", name)
             }
+            Error::IncludeFailed { path, error, .. } => {
+                write!(f, "Can't include {}:\n{}", path.display(), error)
+            }
+            Error::IncludeCycle { chain, .. } => {
+                let chain: Vec<String> = chain.iter().map(|p| p.display().to_string()).collect();
+                write!(f, "Include cycle: {}", chain.join(" -> "))
+            }
+            Error::UnresolvedLabel { name, .. } => {
+                write!(f, "Unresolved label: {}", name)
+            }
+            Error::DuplicateLabel { name, .. } => {
+                write!(f, "Label is defined more than once: {}", name)
+            }
+            Error::IntegerOverflow { value, format, .. } => {
+                write!(f, "Value {} doesn't fit {}", value, format)
+            }
+            Error::DivisionByZero { .. } => {
+                write!(f, "Division by zero")
+            }
+            Error::InvalidShift { amount, .. } => {
+                write!(f, "Invalid shift amount: {}", amount)
+            }
+            Error::LabelInExpressionOperand { .. } => {
+                write!(f, "Labels can only be used in expressions directly, not through constants or functions")
+            }
+            Error::MissingArgument { function, name, .. } => {
+                write!(f, "Missing argument {} for function {}", name, function)
+            }
+            Error::UnknownArgument { function, name, .. } => {
+                write!(f, "Unknown argument {} for function {}", name, function)
+            }
+            Error::DuplicateArgument { function, name, .. } => {
+                write!(
+                    f,
+                    "Argument {} for function {} is passed both by position and by name",
+                    name, function
+                )
+            }
+            Error::OutputRangeOutOfBounds {
+                from, to, length, ..
+            } => {
+                write!(
+                    f,
+                    "Output range {}..{} is out of bounds of {} bytes long output",
+                    from, to, length
+                )
+            }
+            Error::OutputRangeCycle { name, .. } => {
+                write!(
+                    f,
+                    "Output range of function {} contains its own result or results depending on it",
+                    name
+                )
+            }
+            Error::OutputRangeResultLength {
+                name,
+                expected,
+                actual,
+                ..
+            } => {
+                write!(
+                    f,
+                    "Function {} returned {} bytes over output range, but {} bytes were reserved",
+                    name, actual, expected
+                )
+            }
+            Error::InvalidOutputRangeBound { function, name, .. } => {
+                write!(
+                    f,
+                    "Bound {} of function {} must be an offset or a plain label reference",
+                    name, function
+                )
+            }
+            Error::LabelInCondition { .. } => {
+                write!(f, "Labels can't be used in conditions, their offsets are not known yet")
+            }
+            Error::OutputOffsetInCondition { name, .. } => {
+                write!(
+                    f,
+                    "Function {} can't be used in conditions, output offset is not known yet",
+                    name
+                )
+            }
+            Error::LabelInLoopSource { .. } => {
+                write!(f, "Labels can't be used in loop values, their offsets are not known yet")
+            }
+            Error::LoopIterationsExceeded {
+                limit, iterations, ..
+            } => {
+                write!(
+                    f,
+                    "Loop would run {} iterations, more than the limit of {}",
+                    iterations, limit
+                )
+            }
+            Error::AssertionFailed { message, .. } => match message {
+                Some(message) => write!(f, "Assertion failed: {}", message),
+                None => write!(f, "Assertion failed"),
+            },
+            Error::CallDepthExceeded {
+                limit, call_stack, ..
+            } => {
+                // recursion produces long repetitive stacks, its ends are enough to spot the cycle
+                let call_stack = if call_stack.len() > 10 {
+                    let (head, tail) = (&call_stack[..5], &call_stack[call_stack.len() - 5..]);
+                    format!(
+                        "{} -> ... {} more ... -> {}",
+                        head.join(" -> "),
+                        call_stack.len() - 10,
+                        tail.join(" -> ")
+                    )
+                } else {
+                    call_stack.join(" -> ")
+                };
+                write!(
+                    f,
+                    "Function call depth limit of {} exceeded, call stack: {}",
+                    limit, call_stack
+                )
+            }
+            Error::LabelInNativeFunctionArgument { name, .. } => {
+                write!(f, "Labels can't be passed to native function: {}", name)
+            }
         }
     }
 }
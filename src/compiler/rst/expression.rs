@@ -0,0 +1,143 @@
+use std::collections::HashMap;
+
+use num_bigint::{BigInt, Sign};
+
+use crate::compiler::cst::CstOperator;
+use crate::compiler::rst::error::Error;
+use crate::compiler::span::Span;
+use crate::util::byte_buffer::ByteBuffer;
+use crate::util::integer_format::IntegerFormat;
+
+/// Largest allowed shift amount in bits, keeps `1 << 10x99999999` from exhausting memory
+const MAX_SHIFT: usize = 1 << 16;
+
+/// Expression with constants and functions already evaluated, only label offsets may be unknown
+#[derive(Clone, Debug)]
+pub(crate) enum Expression {
+    Value(BigInt),
+    Label {
+        name: String,
+        span: Span,
+    },
+    Binary {
+        operator: CstOperator,
+        left: Box<Expression>,
+        right: Box<Expression>,
+        span: Span,
+    },
+}
+
+impl Expression {
+    /// Combines [left] and [right], folding them right away when both are known
+    pub(crate) fn binary(
+        operator: CstOperator,
+        left: Expression,
+        right: Expression,
+        span: &Span,
+    ) -> Result<Expression, Error> {
+        match (left, right) {
+            (Expression::Value(left), Expression::Value(right)) => {
+                apply(operator, &left, &right, span).map(Expression::Value)
+            }
+            (left, right) => Ok(Expression::Binary {
+                operator,
+                left: Box::new(left),
+                right: Box::new(right),
+                span: span.clone(),
+            }),
+        }
+    }
+
+    /// Evaluates the expression with label offsets known
+    pub(crate) fn evaluate(&self, labels: &HashMap<String, usize>) -> Result<BigInt, Error> {
+        match self {
+            Expression::Value(value) => Ok(value.clone()),
+            Expression::Label { name, span } => labels
+                .get(name)
+                .map(|offset| BigInt::from(*offset))
+                .ok_or_else(|| Error::UnresolvedLabel {
+                    name: name.clone(),
+                    span: span.clone(),
+                }),
+            Expression::Binary {
+                operator,
+                left,
+                right,
+                span,
+            } => apply(*operator, &left.evaluate(labels)?, &right.evaluate(labels)?, span),
+        }
+    }
+}
+
+/// Reads [bytes] as unsigned big endian integer
+pub(crate) fn integer_from_bytes(bytes: &[u8]) -> BigInt {
+    BigInt::from_bytes_be(Sign::Plus, bytes)
+}
+
+/// Encodes [value] in [format], or in as few big endian bytes as possible if there is no format
+pub(crate) fn encode_integer(
+    value: &BigInt,
+    format: Option<IntegerFormat>,
+    span: &Span,
+) -> Result<Vec<u8>, Error> {
+    let overflow = |format: String| Error::IntegerOverflow {
+        value: value.to_string(),
+        format,
+        span: span.clone(),
+    };
+
+    match format {
+        Some(format) => i128::try_from(value)
+            .ok()
+            .and_then(|value| format.encode(value))
+            .ok_or_else(|| overflow(format.to_string())),
+        None if value.sign() == Sign::Minus => Err(overflow("unsigned integer".to_string())),
+        None => Ok(value.to_bytes_be().1),
+    }
+}
+
+/// Pushes [bytes] encoded in [format], integers of signed formats are read as two's complement later
+pub(crate) fn push_integer(bytes: &[u8], format: IntegerFormat, buffer: &mut ByteBuffer) {
+    if format.is_signed() {
+        buffer.push_signed_integer(bytes);
+    } else {
+        bytes.iter().for_each(|byte| buffer.push_byte(*byte));
+    }
+}
+
+fn apply(
+    operator: CstOperator,
+    left: &BigInt,
+    right: &BigInt,
+    span: &Span,
+) -> Result<BigInt, Error> {
+    let shift = || {
+        usize::try_from(right)
+            .ok()
+            .filter(|amount| *amount <= MAX_SHIFT)
+            .ok_or_else(|| Error::InvalidShift {
+                amount: right.to_string(),
+                span: span.clone(),
+            })
+    };
+    let divisor = || {
+        if right.sign() == Sign::NoSign {
+            Err(Error::DivisionByZero { span: span.clone() })
+        } else {
+            Ok(right)
+        }
+    };
+
+    Ok(match operator {
+        CstOperator::Add => left + right,
+        CstOperator::Subtract => left - right,
+        CstOperator::Multiply => left * right,
+        CstOperator::Divide => left / divisor()?,
+        CstOperator::Remainder => left % divisor()?,
+        CstOperator::And => left & right,
+        CstOperator::Or => left | right,
+        CstOperator::Xor => left ^ right,
+        CstOperator::ShiftLeft => left << shift()?,
+        CstOperator::ShiftRight => left >> shift()?,
+    })
+}
@@ -1,8 +1,12 @@
 mod scope;
 mod compiler;
+mod diagnostics;
 mod error;
+mod expression;
 mod node;
+mod warning;
 
 pub(crate) use compiler::RstCompiler;
-pub(crate) use error::Error;
+pub use error::Error;
 pub(crate) use node::*;
+pub use warning::Warning;
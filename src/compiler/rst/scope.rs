@@ -1,8 +1,7 @@
 use std::collections::HashMap;
-use std::path::Path;
+use std::rc::Rc;
 
-use crate::compiler::cst::CstEmitStatement;
-use crate::compiler::native_fn::{NativeFunction, NativeFunctionIndex};
+use crate::compiler::cst::CstFunctionStatement;
 use crate::util::byte_buffer::ByteBuffer;
 use crate::util::id::HexoId;
 
@@ -10,26 +9,39 @@ use crate::util::id::HexoId;
 pub(crate) struct ConstantBinding {
     pub(crate) name: String,
     pub(crate) byte_buffer: ByteBuffer,
+    /// Set when evaluation of the constant has failed
+    pub(crate) poisoned: bool,
 }
 
 #[derive(Clone, Debug)]
 pub(crate) struct FunctionBinding {
-    pub(crate) identifier: HexoId,
+    /// Scope the function is declared in, parent of every activation scope of the function
+    pub(crate) parent: HexoId,
     pub(crate) name: String,
-    pub(crate) emits: Vec<CstEmitStatement>,
+    pub(crate) statement: Rc<CstFunctionStatement>,
+}
+
+/// Branch taken by a conditional statement evaluated in the scope
+#[derive(Clone, Debug)]
+pub(crate) struct ConditionalBinding {
+    pub(crate) id: HexoId,
+    /// Index of the taken branch, [None] when no condition holds and there is no `else`
+    pub(crate) branch: Option<usize>,
+    /// Set when evaluation of a condition has failed
+    pub(crate) poisoned: bool,
 }
 
 #[derive(Clone, Debug, Default)]
 pub(crate) struct LocalCompilationScope {
     constant_table: HashMap<String, ConstantBinding>,
     function_table: HashMap<String, FunctionBinding>,
+    conditional_table: HashMap<HexoId, ConditionalBinding>,
     parents: Vec<HexoId>,
 }
 
 #[derive(Clone, Debug, Default)]
 pub(crate) struct CompilationScope {
     local_scopes: HashMap<HexoId, LocalCompilationScope>,
-    native_function_index: NativeFunctionIndex,
 }
 
 impl CompilationScope {
@@ -38,7 +50,7 @@ impl CompilationScope {
     pub(crate) fn bind_local_constant(&mut self, scope_id: HexoId, constant: ConstantBinding) {
         self.local_scopes
             .entry(scope_id)
-            .or_insert_with(LocalCompilationScope::default);
+            .or_default();
 
         let local_scope: &mut LocalCompilationScope = self
             .local_scopes
@@ -76,7 +88,7 @@ impl CompilationScope {
     pub(crate) fn bind_local_function(&mut self, scope_id: HexoId, function: FunctionBinding) {
         self.local_scopes
             .entry(scope_id)
-            .or_insert_with(LocalCompilationScope::default);
+            .or_default();
 
         let local_scope: &mut LocalCompilationScope = self
             .local_scopes
@@ -109,14 +121,36 @@ impl CompilationScope {
         None
     }
 
-    pub(crate) fn get_native_function(&self, name: &str) -> Option<&NativeFunction> {
-        return self.native_function_index.find(name.to_string());
+    pub(crate) fn bind_local_conditional(
+        &mut self,
+        scope_id: HexoId,
+        conditional: ConditionalBinding,
+    ) {
+        self.local_scopes
+            .entry(scope_id)
+            .or_default()
+            .conditional_table
+            .insert(conditional.id, conditional);
+    }
+
+    /// Conditionals are only looked up in the scope they are evaluated in, never in parents
+    pub(crate) fn get_local_conditional(
+        &self,
+        scope_id: HexoId,
+        id: HexoId,
+    ) -> Option<&ConditionalBinding> {
+        self.local_scopes.get(&scope_id)?.conditional_table.get(&id)
+    }
+
+    /// Drops scope that is no longer reachable, like activation scope of a finished function call
+    pub(crate) fn remove_local_scope(&mut self, scope_id: HexoId) {
+        self.local_scopes.remove(&scope_id);
     }
 
     pub(crate) fn bind_parents(&mut self, scope_id: HexoId, parents: Vec<HexoId>) {
         self.local_scopes
             .entry(scope_id)
-            .or_insert_with(LocalCompilationScope::default);
+            .or_default();
 
         let local_scope: &mut LocalCompilationScope = self
             .local_scopes
@@ -136,7 +170,7 @@ impl LocalCompilationScope {
     }
 
     fn get_constant(&self, name: &String) -> Option<&ConstantBinding> {
-        return self.constant_table.get(name);
+        self.constant_table.get(name)
     }
 
     fn bind_function(&mut self, function: FunctionBinding) {
@@ -144,7 +178,7 @@ impl LocalCompilationScope {
     }
 
     fn get_function(&self, name: &String) -> Option<&FunctionBinding> {
-        return self.function_table.get(name);
+        self.function_table.get(name)
     }
 
     fn attach_parent(&mut self, parent_id: HexoId) {
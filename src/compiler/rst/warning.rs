@@ -0,0 +1,57 @@
+use std::path::PathBuf;
+
+use crate::compiler::span::Span;
+
+/// Suspicious code that doesn't stop the compilation
+#[derive(Clone, Debug)]
+pub enum Warning {
+    IncludedEmitsIgnored {
+        path: PathBuf,
+        span: Span,
+    },
+    /// Constant declared without `$?` is replaced by a definition
+    ConstantOverridden {
+        name: String,
+        span: Span,
+    },
+    /// Definition neither replaces nor is used by anything in the file, likely misspelled
+    UnusedDefinition {
+        name: String,
+        span: Span,
+    },
+}
+
+impl Warning {
+    /// Location of the source code that caused the warning
+    pub fn span(&self) -> &Span {
+        match self {
+            Warning::IncludedEmitsIgnored { span, .. } => span,
+            Warning::ConstantOverridden { span, .. } => span,
+            Warning::UnusedDefinition { span, .. } => span,
+        }
+    }
+}
+
+impl std::fmt::Display for Warning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Warning::IncludedEmitsIgnored { path, .. } => {
+                write!(f, "Emit statements of included file {:?} are ignored", path)
+            }
+            Warning::ConstantOverridden { name, .. } => {
+                write!(
+                    f,
+                    "Constant {} is overridden by definition, but isn't declared with `$?`",
+                    name
+                )
+            }
+            Warning::UnusedDefinition { name, .. } => {
+                write!(
+                    f,
+                    "Definition {} doesn't match any constant declared with `$?`",
+                    name
+                )
+            }
+        }
+    }
+}
@@ -0,0 +1,132 @@
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Source text shared by all [Span]s of a single compilation unit
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct SpanSource {
+    path: PathBuf,
+    content: String,
+}
+
+impl SpanSource {
+    pub(crate) fn new(path: &Path, content: &str) -> SpanSource {
+        SpanSource {
+            path: path.to_path_buf(),
+            content: content.to_string(),
+        }
+    }
+}
+
+/// Location of a syntax element in the source file, used to point errors at the offending code
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Span {
+    source: Arc<SpanSource>,
+    start: usize,
+    end: usize,
+}
+
+impl Span {
+    pub(crate) fn new(source: Arc<SpanSource>, start: usize, end: usize) -> Span {
+        Span { source, start, end }
+    }
+
+    /// Span covering both [self] and [other], both must point into the same source
+    pub(crate) fn join(&self, other: &Span) -> Span {
+        Span::new(
+            self.source.clone(),
+            self.start.min(other.start),
+            self.end.max(other.end),
+        )
+    }
+
+    /// Part of the span from [start] to [end], both are byte offsets relative to the span start
+    pub(crate) fn slice(&self, start: usize, end: usize) -> Span {
+        Span::new(
+            self.source.clone(),
+            self.start + start,
+            (self.start + end).min(self.end),
+        )
+    }
+
+    /// Path of the source file this span points into
+    pub fn path(&self) -> &Path {
+        self.source.path.as_path()
+    }
+
+    /// 1-based line of the span start
+    pub fn line(&self) -> usize {
+        self.source.content[..self.start].matches('\n').count() + 1
+    }
+
+    /// 1-based column of the span start, counted in characters
+    pub fn column(&self) -> usize {
+        self.source.content[self.line_start()..self.start].chars().count() + 1
+    }
+
+    /// Renders the source line of the span with the spanned part underlined by carets
+    pub fn snippet(&self) -> String {
+        let line_start = self.line_start();
+        let line_end = self.source.content[self.start..]
+            .find('\n')
+            .map(|i| self.start + i)
+            .unwrap_or(self.source.content.len());
+        let line_text = self.source.content[line_start..line_end].trim_end_matches('\r');
+
+        let line_number = self.line().to_string();
+        let gutter = " ".repeat(line_number.len());
+        let underline_offset = self.column() - 1;
+        let underline_length = self.source.content[self.start..self.end.min(line_end)]
+            .chars()
+            .count()
+            .max(1);
+
+        format!(
+            "{gutter}--> {self}\n{gutter} |\n{line_number} | {line_text}\n{gutter} | {}{}",
+            " ".repeat(underline_offset),
+            "^".repeat(underline_length)
+        )
+    }
+
+    fn line_start(&self) -> usize {
+        self.source.content[..self.start]
+            .rfind('\n')
+            .map(|i| i + 1)
+            .unwrap_or(0)
+    }
+}
+
+impl Display for Span {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}:{}", self.path().display(), self.line(), self.column())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::Path;
+    use std::sync::Arc;
+
+    use super::{Span, SpanSource};
+
+    #[test]
+    fn line_and_column() {
+        let source = Arc::new(SpanSource::new(Path::new("a.hexo"), "> 00\n> 01 $hdr\n"));
+        let span = Span::new(source, 11, 14);
+
+        assert_eq!(span.line(), 2);
+        assert_eq!(span.column(), 7);
+        assert_eq!(span.to_string(), "a.hexo:2:7");
+    }
+
+    #[test]
+    fn snippet() {
+        let source = Arc::new(SpanSource::new(Path::new("a.hexo"), "> 00\n> 01 $hdr\n"));
+        let span = Span::new(source, 11, 14);
+
+        assert_eq!(
+            span.snippet(),
+            " --> a.hexo:2:7\n  |\n2 | > 01 $hdr\n  |       ^^^"
+        );
+    }
+}
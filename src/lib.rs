@@ -0,0 +1,89 @@
+#![cfg_attr(test, feature(test))]
+#![allow(clippy::redundant_field_names)]
+
+//! Hexo is a tiny binary writer utility.
+//!
+//! Besides the `hexo` command line tool, the compiler can be used in-process,
+//! for example from a `build.rs` script:
+//!
+//! ```no_run
+//! use hexo::{FileCompilerSource, HexoCompiler, HexoCompilerContext};
+//! use std::path::Path;
+//!
+//! let compiler = HexoCompiler::new(HexoCompilerContext::new(true));
+//! let source = FileCompilerSource::new(Path::new("header.hexo"));
+//! let compilation = compiler.compile(&source).unwrap();
+//!
+//! std::fs::write("header.bin", compilation.content).unwrap();
+//! ```
+
+mod cli;
+mod compiler;
+mod util;
+
+/// Entry point of the `hexo` binary, not a part of the library API
+#[doc(hidden)]
+pub use cli::Cli;
+pub use compiler::{
+    AstError, Compilation, CompilerSource, CstError, Error, FileCompilerSource, HexoCompiler,
+    HexoCompilerContext, LiteralCompilerSource, NativeFunction, NativeFunctionArguments,
+    NativeFunctionDefinition, NativeFunctionError, NativeFunctionParameter,
+    NativeFunctionSignature, NativeParameterKind, RstError, Span, Warning,
+};
+pub use util::byte_buffer::ByteBuffer;
+pub use util::logger::{HexoLogger, LogLevel};
+
+#[cfg(test)]
+mod test {
+    #[cfg(test)]
+    mod integration {
+        use std::fs::File;
+        use std::io::Read;
+        use std::path::Path;
+
+        use crate::{FileCompilerSource, HexoCompiler, HexoCompilerContext};
+
+        macro_rules! integration_test_case {
+            ($case:ident) => {
+                #[test]
+                fn $case() {
+                    let case_name = stringify!($case);
+
+                    let input_file_path = format!("samples/{case_name}/input.hexo");
+                    let expected_file_path = format!("samples/{case_name}/output.bin");
+
+                    let compiler = HexoCompiler::new(HexoCompilerContext::new(false));
+                    let source = FileCompilerSource::new(Path::new(&input_file_path));
+                    let actual_content = compiler.compile(&source).unwrap().content;
+
+                    let mut expected_file = File::open(expected_file_path).unwrap();
+                    let mut expected_content = vec![];
+                    expected_file.read_to_end(&mut expected_content).unwrap();
+
+                    assert_eq!(expected_content, actual_content)
+                }
+            };
+        }
+
+        integration_test_case!(assertions);
+        integration_test_case!(checksum);
+        integration_test_case!(conditional);
+        #[cfg(feature = "digest")]
+        integration_test_case!(digest);
+        integration_test_case!(expressions);
+        integration_test_case!(fill);
+        integration_test_case!(function_params);
+        integration_test_case!(include);
+        integration_test_case!(integers);
+        integration_test_case!(java_object);
+        integration_test_case!(labels);
+        integration_test_case!(len);
+        integration_test_case!(loops);
+        integration_test_case!(pad_left);
+        integration_test_case!(pad_right);
+        integration_test_case!(read_file);
+        integration_test_case!(text_codec);
+        integration_test_case!(text_encoding);
+        integration_test_case!(varint);
+    }
+}
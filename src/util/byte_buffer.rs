@@ -1,9 +1,23 @@
 use std::fmt::{Debug, Formatter};
 use std::string::FromUtf8Error;
 
+use crate::util::id::HexoId;
+
+/// Reserved region of [width] bytes at [offset], moves along with the bytes when buffers are combined
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) struct ByteMarker {
+    pub(crate) id: HexoId,
+    pub(crate) offset: usize,
+    pub(crate) width: usize,
+}
+
+/// Bytes produced by the compiler, the value type of constants and function arguments
 #[derive(Clone, Default)]
-pub(crate) struct ByteBuffer {
+pub struct ByteBuffer {
     inner: Vec<u8>,
+    markers: Vec<ByteMarker>,
+    /// Bytes are a single integer written in a signed format, like `10x-2:i8`
+    signed: bool,
 }
 
 impl Debug for ByteBuffer {
@@ -14,30 +28,95 @@ impl Debug for ByteBuffer {
 
 impl ByteBuffer {
 
-    pub(crate) fn push_byte(&mut self, byte: u8) {
+    pub fn push_byte(&mut self, byte: u8) {
+        self.signed = false;
         self.inner.push(byte);
     }
 
-    pub(crate) fn push_string(&mut self, string: String) {
+    pub fn push_string(&mut self, string: String) {
+        self.signed = false;
         self.inner.extend_from_slice(string.as_bytes());
     }
 
     pub(crate) fn push_u32_shrunk(&mut self, num: u32) {
+        self.signed = false;
         self.inner.extend(Self::_to_shrunk_bytes(num));
     }
 
-    pub(crate) fn push_byte_buffer(&mut self, other: &ByteBuffer) {
+    /// Pushes two's complement [bytes], buffer stays signed only if they are all of its content
+    pub(crate) fn push_signed_integer(&mut self, bytes: &[u8]) {
+        self.signed = self.inner.is_empty();
+        self.inner.extend_from_slice(bytes);
+    }
+
+    pub fn push_byte_buffer(&mut self, other: &ByteBuffer) {
+        self.signed = self.inner.is_empty() && other.signed;
+        let offset = self.inner.len();
+        self.markers.extend(other.markers.iter().map(|marker| ByteMarker {
+            offset: marker.offset + offset,
+            ..*marker
+        }));
         self.inner.extend(other.to_vec());
     }
 
+    /// Reserves [width] zero bytes to be patched later and marks them with [id]
+    pub(crate) fn push_marker(&mut self, id: HexoId, width: usize) {
+        self.signed = false;
+        self.markers.push(ByteMarker {
+            id,
+            offset: self.inner.len(),
+            width,
+        });
+        self.inner.extend(vec![0; width]);
+    }
+
+    /// Moves bytes from [at] on into a new buffer, along with markers placed in them
+    pub(crate) fn split_off(&mut self, at: usize) -> ByteBuffer {
+        // only a split at the start keeps the integer whole
+        let signed = self.signed && at == 0;
+        self.signed = false;
+        let inner = self.inner.split_off(at);
+        let (moved, kept): (Vec<_>, Vec<_>) = self
+            .markers
+            .drain(..)
+            .partition(|marker| marker.offset >= at);
+        self.markers = kept;
+
+        ByteBuffer {
+            inner,
+            markers: moved
+                .into_iter()
+                .map(|marker| ByteMarker {
+                    offset: marker.offset - at,
+                    ..marker
+                })
+                .collect(),
+            signed,
+        }
+    }
+
+    pub(crate) fn markers(&self) -> &Vec<ByteMarker> {
+        &self.markers
+    }
+
+    /// Overwrites bytes reserved by [marker] with [bytes]
+    pub(crate) fn patch(&mut self, marker: &ByteMarker, bytes: &[u8]) {
+        self.inner[marker.offset..marker.offset + marker.width].copy_from_slice(bytes);
+    }
+
     /// Moves the byte buffer to the left by the specified [size]
     pub(crate) fn pad_left(&mut self, size: usize) {
         let padding = size.checked_sub(self.inner.len());
         if let Some(padding) = padding {
             if padding > 0 {
+                self.signed = false;
                 let mut padding_vec = vec![0; padding];
                 padding_vec.append(&mut self.inner);
                 self.inner = padding_vec;
+
+                for marker in &mut self.markers {
+                    marker.offset += padding;
+                }
             }
         }
     }
@@ -48,6 +127,7 @@ impl ByteBuffer {
 
         if let Some(padding) = padding {
             if padding > 0 {
+                self.signed = false;
                 let mut padding_vec = vec![0; padding];
                 self.inner.append(padding_vec.as_mut());
             }
@@ -55,47 +135,66 @@ impl ByteBuffer {
     }
 
     /// Returns the length of the byte buffer
-    pub(crate) fn len(&self) -> usize {
+    pub fn len(&self) -> usize {
         self.inner.len()
     }
 
-    pub(crate) fn as_usize_unsafe(&self) -> usize {
-        let mut padded = self.clone();
-        padded.pad_left(4);
-        ((padded.inner[0] as usize) << 24)
-            + ((padded.inner[1] as usize) << 16)
-            + ((padded.inner[2] as usize) << 8)
-            + (padded.inner[3] as usize)
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// Whether bytes should be read as two's complement, see [Self::push_signed_integer]
+    pub(crate) fn is_signed(&self) -> bool {
+        self.signed
+    }
+
+    /// Reads bytes as unsigned big endian integer, returns [None] if it doesn't fit [usize]
+    pub fn to_usize(&self) -> Option<usize> {
+        let significant: Vec<u8> = self.inner.iter().copied().skip_while(|b| *b == 0).collect();
+        if significant.len() > size_of::<usize>() {
+            return None;
+        }
+
+        Some(
+            significant
+                .into_iter()
+                .fold(0, |acc, byte| (acc << 8) | byte as usize),
+        )
     }
 
     /// Clones inner representation of the byte buffer and returns Vec<u8> of it
-    pub(crate) fn to_vec(&self) -> Vec<u8> {
+    pub fn to_vec(&self) -> Vec<u8> {
         self.inner.clone()
     }
 
-    pub(crate) fn to_string(&self) -> Result<String, FromUtf8Error> {
+    pub fn to_string(&self) -> Result<String, FromUtf8Error> {
         String::from_utf8(self.inner.clone())
     }
 
+    /// Big endian bytes of [value] without leading zeros
     fn _to_shrunk_bytes(value: u32) -> Vec<u8> {
-        let mut bytes = Vec::new();
-        let mut value = value;
-        while value > 0 {
-            bytes.push((value & 0xFF) as u8);
-            value >>= 8;
-        }
-        bytes
+        value
+            .to_be_bytes()
+            .into_iter()
+            .skip_while(|byte| *byte == 0)
+            .collect()
     }
 }
 
 impl From<Vec<u8>> for ByteBuffer {
     fn from(value: Vec<u8>) -> Self {
-        ByteBuffer { inner: value }
+        ByteBuffer {
+            inner: value,
+            markers: Vec::new(),
+            signed: false,
+        }
     }
 }
 
+#[cfg(test)]
 mod test {
     use super::ByteBuffer;
+    use crate::util::id::HexoId;
 
     #[test]
     fn byte_push() {
@@ -121,6 +220,58 @@ mod test {
         );
     }
 
+    #[test]
+    fn markers_move_with_bytes() {
+        let id = HexoId::next();
+        let mut inner = ByteBuffer::default();
+        inner.push_byte(0x01);
+        inner.push_marker(id, 2);
+
+        let mut buffer = ByteBuffer::default();
+        buffer.push_byte(0xFF);
+        buffer.push_byte_buffer(&inner);
+
+        let marker = buffer.markers()[0];
+        assert_eq!((marker.id, marker.offset, marker.width), (id, 2, 2));
+
+        buffer.patch(&marker, &[0xAA, 0xBB]);
+        assert_eq!(buffer.to_vec(), vec![0xFF, 0x01, 0xAA, 0xBB]);
+    }
+
+    #[test]
+    fn markers_are_split_off() {
+        let (kept, moved) = (HexoId::next(), HexoId::next());
+        let mut buffer = ByteBuffer::default();
+        buffer.push_marker(kept, 1);
+        buffer.push_byte(0x01);
+        buffer.push_marker(moved, 2);
+
+        let tail = buffer.split_off(2);
+
+        assert_eq!(buffer.to_vec(), vec![0x00, 0x01]);
+        assert_eq!(buffer.markers().len(), 1);
+        assert_eq!(buffer.markers()[0].id, kept);
+
+        let marker = tail.markers()[0];
+        assert_eq!((marker.id, marker.offset, marker.width), (moved, 0, 2));
+        assert_eq!(tail.len(), 2);
+    }
+
+    #[test]
+    fn signed_integer_push() {
+        let mut buffer = ByteBuffer::default();
+        buffer.push_signed_integer(&[0xFE]);
+
+        let mut copy = ByteBuffer::default();
+        copy.push_byte_buffer(&buffer);
+        assert!(buffer.is_signed() && copy.is_signed());
+
+        // anything else in the buffer makes it a plain sequence of bytes
+        buffer.push_byte(0x00);
+        copy.push_signed_integer(&[0xFE]);
+        assert!(!buffer.is_signed() && !copy.is_signed());
+    }
+
     #[test]
     fn u32_push() {
         let mut buffer = ByteBuffer::default();
@@ -129,5 +280,17 @@ mod test {
         assert_eq!(buffer.len(), 1);
 
         assert_eq!(buffer.to_vec(), vec![13]);
+
+        buffer.push_u32_shrunk(256);
+
+        assert_eq!(buffer.to_vec(), vec![13, 0x01, 0x00]);
+    }
+
+    #[test]
+    fn to_usize() {
+        assert_eq!(ByteBuffer::default().to_usize(), Some(0));
+        assert_eq!(ByteBuffer::from(vec![0x01, 0x00]).to_usize(), Some(256));
+        assert_eq!(ByteBuffer::from(vec![0x00; 16]).to_usize(), Some(0));
+        assert_eq!(ByteBuffer::from(vec![0x01; 16]).to_usize(), None);
     }
 }
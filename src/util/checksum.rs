@@ -0,0 +1,154 @@
+/// Parameters of a CRC algorithm in the Rocksoft model, see "A Painless Guide to CRC Error Detection Algorithms"
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) struct CrcAlgorithm {
+    /// Width in bits, one of 8, 16, 32 or 64
+    pub(crate) width: u32,
+    /// Polynomial without the leading term, in normal (MSB first) notation
+    pub(crate) poly: u64,
+    pub(crate) init: u64,
+    pub(crate) reflect_in: bool,
+    pub(crate) reflect_out: bool,
+    pub(crate) xor_out: u64,
+}
+
+impl CrcAlgorithm {
+    pub(crate) const CRC8_SMBUS: CrcAlgorithm = CrcAlgorithm {
+        width: 8,
+        poly: 0x07,
+        init: 0x00,
+        reflect_in: false,
+        reflect_out: false,
+        xor_out: 0x00,
+    };
+
+    pub(crate) const CRC16_ARC: CrcAlgorithm = CrcAlgorithm {
+        width: 16,
+        poly: 0x8005,
+        init: 0x0000,
+        reflect_in: true,
+        reflect_out: true,
+        xor_out: 0x0000,
+    };
+
+    /// CRC-32 of PNG, ZIP and Ethernet
+    pub(crate) const CRC32_ISO_HDLC: CrcAlgorithm = CrcAlgorithm {
+        width: 32,
+        poly: 0x04C11DB7,
+        init: 0xFFFFFFFF,
+        reflect_in: true,
+        reflect_out: true,
+        xor_out: 0xFFFFFFFF,
+    };
+
+    pub(crate) const CRC64_XZ: CrcAlgorithm = CrcAlgorithm {
+        width: 64,
+        poly: 0x42F0E1EBA9EA3693,
+        init: 0xFFFFFFFFFFFFFFFF,
+        reflect_in: true,
+        reflect_out: true,
+        xor_out: 0xFFFFFFFFFFFFFFFF,
+    };
+
+    /// Largest value fitting [width] bits
+    pub(crate) fn mask(&self) -> u64 {
+        u64::MAX >> (64 - self.width)
+    }
+
+    pub(crate) fn checksum(&self, data: &[u8]) -> u64 {
+        let mask = self.mask();
+        let top_bit = 1u64 << (self.width - 1);
+        let mut crc = self.init & mask;
+
+        for byte in data {
+            let byte = if self.reflect_in {
+                byte.reverse_bits()
+            } else {
+                *byte
+            };
+            crc ^= (byte as u64) << (self.width - 8);
+
+            for _ in 0..8 {
+                crc = if crc & top_bit != 0 {
+                    (crc << 1) ^ self.poly
+                } else {
+                    crc << 1
+                };
+            }
+            crc &= mask;
+        }
+
+        if self.reflect_out {
+            crc = crc.reverse_bits() >> (64 - self.width);
+        }
+
+        (crc ^ self.xor_out) & mask
+    }
+}
+
+pub(crate) fn adler32(data: &[u8]) -> u32 {
+    const MODULO: u32 = 65521;
+
+    let mut a: u32 = 1;
+    let mut b: u32 = 0;
+    for byte in data {
+        a = (a + *byte as u32) % MODULO;
+        b = (b + a) % MODULO;
+    }
+
+    (b << 16) | a
+}
+
+pub(crate) fn fletcher16(data: &[u8]) -> u16 {
+    let mut sum1: u16 = 0;
+    let mut sum2: u16 = 0;
+    for byte in data {
+        sum1 = (sum1 + *byte as u16) % 255;
+        sum2 = (sum2 + sum1) % 255;
+    }
+
+    (sum2 << 8) | sum1
+}
+
+/// Sums little endian 16 bit words, odd trailing byte is padded with zero
+pub(crate) fn fletcher32(data: &[u8]) -> u32 {
+    let mut sum1: u32 = 0;
+    let mut sum2: u32 = 0;
+    for word in data.chunks(2) {
+        let word = word[0] as u32 | (*word.get(1).unwrap_or(&0) as u32) << 8;
+        sum1 = (sum1 + word) % 65535;
+        sum2 = (sum2 + sum1) % 65535;
+    }
+
+    (sum2 << 16) | sum1
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const CHECK: &[u8] = b"123456789";
+
+    #[test]
+    fn crc_check_values() {
+        assert_eq!(CrcAlgorithm::CRC8_SMBUS.checksum(CHECK), 0xF4);
+        assert_eq!(CrcAlgorithm::CRC16_ARC.checksum(CHECK), 0xBB3D);
+        assert_eq!(CrcAlgorithm::CRC32_ISO_HDLC.checksum(CHECK), 0xCBF43926);
+        assert_eq!(CrcAlgorithm::CRC64_XZ.checksum(CHECK), 0x995DC9BBDF1939FA);
+
+        let xmodem = CrcAlgorithm {
+            poly: 0x1021,
+            reflect_in: false,
+            reflect_out: false,
+            ..CrcAlgorithm::CRC16_ARC
+        };
+        assert_eq!(xmodem.checksum(CHECK), 0x31C3);
+    }
+
+    #[test]
+    fn adler_and_fletcher() {
+        assert_eq!(adler32(b"Wikipedia"), 0x11E60398);
+        assert_eq!(fletcher16(b"abcde"), 0xC8F0);
+        assert_eq!(fletcher32(b"abcde"), 0xF04FC729);
+        assert_eq!(fletcher32(b"abcdef"), 0x56502D2A);
+    }
+}
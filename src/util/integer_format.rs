@@ -0,0 +1,124 @@
+use std::fmt::{Display, Formatter};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum Endianness {
+    Big,
+    Little,
+}
+
+/// Fixed width integer encoding, written in sources as `u8`, `i16le`, `u32be`, ...
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) struct IntegerFormat {
+    width: usize,
+    signed: bool,
+    endianness: Endianness,
+}
+
+impl IntegerFormat {
+    pub(crate) const U32_BE: IntegerFormat = IntegerFormat {
+        width: 4,
+        signed: false,
+        endianness: Endianness::Big,
+    };
+
+    /// Parses format name, endianness defaults to big endian when suffix is omitted
+    pub(crate) fn parse(name: &str) -> Option<IntegerFormat> {
+        let (signed, rest) = match name.split_at_checked(1)? {
+            ("u", rest) => (false, rest),
+            ("i", rest) => (true, rest),
+            _ => return None,
+        };
+
+        let (bits, endianness) = if let Some(bits) = rest.strip_suffix("le") {
+            (bits, Endianness::Little)
+        } else if let Some(bits) = rest.strip_suffix("be") {
+            (bits, Endianness::Big)
+        } else {
+            (rest, Endianness::Big)
+        };
+
+        let width = match bits {
+            "8" => 1,
+            "16" => 2,
+            "32" => 4,
+            "64" => 8,
+            _ => return None,
+        };
+
+        Some(IntegerFormat {
+            width,
+            signed,
+            endianness,
+        })
+    }
+
+    pub(crate) fn width(&self) -> usize {
+        self.width
+    }
+
+    pub(crate) fn is_signed(&self) -> bool {
+        self.signed
+    }
+
+    /// Encodes [value] in this format, returns [None] if it doesn't fit
+    pub(crate) fn encode(&self, value: i128) -> Option<Vec<u8>> {
+        let bits = self.width as u32 * 8;
+        let (min, max) = if self.signed {
+            (-(1i128 << (bits - 1)), (1i128 << (bits - 1)) - 1)
+        } else {
+            (0, (1i128 << bits) - 1)
+        };
+
+        if value < min || value > max {
+            return None;
+        }
+
+        let mut bytes = value.to_le_bytes()[..self.width].to_vec();
+        if self.endianness == Endianness::Big {
+            bytes.reverse();
+        }
+
+        Some(bytes)
+    }
+}
+
+impl Display for IntegerFormat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let sign = if self.signed { "i" } else { "u" };
+        let endianness = match self.endianness {
+            _ if self.width == 1 => "",
+            Endianness::Big => "be",
+            Endianness::Little => "le",
+        };
+
+        write!(f, "{}{}{}", sign, self.width * 8, endianness)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::IntegerFormat;
+
+    #[test]
+    fn parse() {
+        assert_eq!(IntegerFormat::parse("u32"), Some(IntegerFormat::U32_BE));
+        assert_eq!(IntegerFormat::parse("i16le").unwrap().to_string(), "i16le");
+        assert_eq!(IntegerFormat::parse("u24"), None);
+        assert_eq!(IntegerFormat::parse("f32"), None);
+    }
+
+    #[test]
+    fn encode() {
+        let u16_be = IntegerFormat::parse("u16").unwrap();
+        let u16_le = IntegerFormat::parse("u16le").unwrap();
+        let i8 = IntegerFormat::parse("i8").unwrap();
+
+        assert_eq!(u16_be.encode(256), Some(vec![0x01, 0x00]));
+        assert_eq!(u16_le.encode(256), Some(vec![0x00, 0x01]));
+        assert_eq!(u16_be.encode(65536), None);
+        assert_eq!(u16_be.encode(-1), None);
+        assert_eq!(i8.encode(-1), Some(vec![0xFF]));
+        assert_eq!(i8.encode(-129), None);
+        assert_eq!(i8.encode(128), None);
+    }
+}
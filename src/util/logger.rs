@@ -5,7 +5,7 @@ use console::style;
 use lazy_static::lazy_static;
 
 #[derive(PartialOrd, PartialEq, Clone, Debug)]
-pub(crate) enum LogLevel {
+pub enum LogLevel {
     Debug,
     Info,
     Warn,
@@ -35,7 +35,7 @@ impl Display for LogLevel {
     }
 }
 
-pub(crate) struct HexoLogger {
+pub struct HexoLogger {
     level: LogLevel,
 }
 
@@ -44,7 +44,7 @@ impl HexoLogger {
         &self.level
     }
 
-    pub(crate) fn set_level(level: &LogLevel) {
+    pub fn set_level(level: &LogLevel) {
         INSTANCE.lock().unwrap().level = level.clone();
     }
 
@@ -52,6 +52,7 @@ impl HexoLogger {
         eprintln!("{} :> {} {}", style("error").bright(), style(location).blue(), style(message).red());
     }
 
+    #[allow(dead_code)]
     pub(crate) fn info(&self, location: &str, message: &str) {
         eprintln!("{} :> {} {}", style("info").bright(), style(location).blue(), style(message).blue());
     }
@@ -61,7 +62,7 @@ impl HexoLogger {
     }
 
     pub(crate) fn warn(&self, location: &str, message: &str) {
-        println!("{} :> {} {}", style("warn").bright(),style(location).blue(), style(message).yellow());
+        eprintln!("{} :> {} {}", style("warn").bright(),style(location).blue(), style(message).yellow());
     }
 
     pub(crate) fn output(&self, message: &str) {
@@ -72,8 +73,9 @@ impl HexoLogger {
 lazy_static!(
     pub(crate) static ref INSTANCE: Mutex<HexoLogger> =
         Mutex::new(
+            // embedding applications opt in to logging, the CLI sets the level from its arguments
             HexoLogger {
-                level: LogLevel::Debug
+                level: LogLevel::None
             }
     );
 );
@@ -106,6 +108,7 @@ macro_rules! error {
     };
 }
 
+#[allow(unused_macros)]
 macro_rules! info {
     ($($arg:tt)*) => {
         {
@@ -143,4 +146,5 @@ macro_rules! output {
     };
 }
 
+#[allow(unused_imports)]
 pub(crate) use {debug, output, error, info, warning};
\ No newline at end of file
@@ -1,6 +1,11 @@
 pub(crate) mod byte_buffer;
+pub(crate) mod checksum;
 pub(crate) mod id;
+pub(crate) mod integer_format;
 pub(crate) mod logger;
+pub(crate) mod text_codec;
+pub(crate) mod text_encoding;
+pub(crate) mod varint;
 mod defer;
 
 pub(crate) use defer::*;
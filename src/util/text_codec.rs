@@ -0,0 +1,269 @@
+use std::fmt::{Display, Formatter};
+
+/// Binary to text encodings of RFC 4648 sharing the same bit packing
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum BaseAlphabet {
+    Base64,
+    /// URL and filename safe base64, `-` and `_` instead of `+` and `/`
+    Base64Url,
+    Base32,
+}
+
+impl BaseAlphabet {
+    pub(crate) const ALL: [BaseAlphabet; 3] = [
+        BaseAlphabet::Base64,
+        BaseAlphabet::Base64Url,
+        BaseAlphabet::Base32,
+    ];
+
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            BaseAlphabet::Base64 => "base64",
+            BaseAlphabet::Base64Url => "base64url",
+            BaseAlphabet::Base32 => "base32",
+        }
+    }
+
+    fn symbols(&self) -> &'static [u8] {
+        match self {
+            BaseAlphabet::Base64 => {
+                b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/"
+            }
+            BaseAlphabet::Base64Url => {
+                b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_"
+            }
+            BaseAlphabet::Base32 => b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567",
+        }
+    }
+
+    /// Bits encoded by a single symbol
+    fn bits(&self) -> u32 {
+        match self {
+            BaseAlphabet::Base64 | BaseAlphabet::Base64Url => 6,
+            BaseAlphabet::Base32 => 5,
+        }
+    }
+
+    /// Encoded length is padded with `=` to multiple of this
+    fn block(&self) -> usize {
+        match self {
+            BaseAlphabet::Base64 | BaseAlphabet::Base64Url => 4,
+            BaseAlphabet::Base32 => 8,
+        }
+    }
+
+    pub(crate) fn encode(&self, data: &[u8], padding: bool) -> String {
+        let symbols = self.symbols();
+        let bits = self.bits();
+        let mask = (1u32 << bits) - 1;
+
+        let mut encoded = String::new();
+        let mut buffer: u32 = 0;
+        let mut buffered = 0;
+        for byte in data {
+            buffer = (buffer << 8) | *byte as u32;
+            buffered += 8;
+            while buffered >= bits {
+                buffered -= bits;
+                encoded.push(symbols[((buffer >> buffered) & mask) as usize] as char);
+            }
+        }
+        if buffered > 0 {
+            encoded.push(symbols[((buffer << (bits - buffered)) & mask) as usize] as char);
+        }
+
+        if padding {
+            while !encoded.len().is_multiple_of(self.block()) {
+                encoded.push('=');
+            }
+        }
+
+        encoded
+    }
+
+    /// Decodes [text] ignoring whitespace, padding is optional. Base32 is case insensitive
+    pub(crate) fn decode(&self, text: &str) -> Result<Vec<u8>, String> {
+        let symbols = self.symbols();
+        let bits = self.bits();
+
+        let mut decoded = Vec::new();
+        let mut buffer: u32 = 0;
+        let mut buffered = 0;
+        let mut padded = false;
+        for (index, character) in text.chars().enumerate() {
+            if character.is_whitespace() {
+                continue;
+            }
+            if character == '=' {
+                padded = true;
+                continue;
+            }
+
+            let character = match self {
+                BaseAlphabet::Base32 => character.to_ascii_uppercase(),
+                _ => character,
+            };
+            let value = symbols
+                .iter()
+                .position(|symbol| *symbol as char == character)
+                .filter(|_| !padded)
+                .ok_or_else(|| {
+                    format!("unexpected character {:?} at index {}", character, index)
+                })?;
+
+            buffer = (buffer << bits) | value as u32;
+            buffered += bits;
+            if buffered >= 8 {
+                buffered -= 8;
+                decoded.push((buffer >> buffered) as u8);
+                buffer &= (1 << buffered) - 1;
+            }
+        }
+
+        if buffered >= bits {
+            return Err("input is truncated".to_string());
+        }
+
+        Ok(decoded)
+    }
+}
+
+impl Display for BaseAlphabet {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Strips whitespace, `,` `:` `-` `_` separators and `0x` prefixes from a hex dump
+pub(crate) fn strip_hex_separators(text: &str) -> String {
+    text.split(|c: char| c.is_whitespace() || matches!(c, ',' | ':' | '-' | '_'))
+        .map(|token| {
+            token
+                .strip_prefix("0x")
+                .or_else(|| token.strip_prefix("0X"))
+                .unwrap_or(token)
+        })
+        .collect()
+}
+
+/// Body of the first PEM block with [label], or of the first block when [label] is [None]
+pub(crate) fn pem_body(text: &str, label: Option<&str>) -> Result<String, String> {
+    let mut lines = text.lines().map(str::trim);
+
+    loop {
+        let Some(line) = lines.next() else {
+            return Err(match label {
+                Some(label) => format!("no {} block found", label),
+                None => "no PEM block found".to_string(),
+            });
+        };
+
+        let Some(block_label) = line
+            .strip_prefix("-----BEGIN ")
+            .and_then(|line| line.strip_suffix("-----"))
+        else {
+            continue;
+        };
+        if label.is_some_and(|label| label != block_label) {
+            continue;
+        }
+
+        let end = format!("-----END {}-----", block_label);
+        let mut body = String::new();
+        for line in lines.by_ref() {
+            if line == end {
+                return Ok(body);
+            }
+            // RFC 1421 headers like `Proc-Type: 4,ENCRYPTED` are not part of the body
+            if !line.contains(':') {
+                body.push_str(line);
+            }
+        }
+
+        return Err(format!("{} block is not closed", block_label));
+    }
+}
+
+pub(crate) fn pem_encode(data: &[u8], label: &str) -> String {
+    let body = BaseAlphabet::Base64.encode(data, true);
+
+    let mut pem = format!("-----BEGIN {}-----\n", label);
+    for line in body.as_bytes().chunks(64) {
+        pem.push_str(std::str::from_utf8(line).expect("base64 is ascii"));
+        pem.push('\n');
+    }
+    pem.push_str(&format!("-----END {}-----\n", label));
+
+    pem
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn rfc4648_vectors() {
+        let vectors = [
+            ("", "", ""),
+            ("f", "Zg==", "MY======"),
+            ("fo", "Zm8=", "MZXQ===="),
+            ("foo", "Zm9v", "MZXW6==="),
+            ("foob", "Zm9vYg==", "MZXW6YQ="),
+            ("fooba", "Zm9vYmE=", "MZXW6YTB"),
+            ("foobar", "Zm9vYmFy", "MZXW6YTBOI======"),
+        ];
+
+        for (data, base64, base32) in vectors {
+            assert_eq!(BaseAlphabet::Base64.encode(data.as_bytes(), true), base64);
+            assert_eq!(BaseAlphabet::Base32.encode(data.as_bytes(), true), base32);
+            assert_eq!(
+                BaseAlphabet::Base64.decode(base64).unwrap(),
+                data.as_bytes()
+            );
+            assert_eq!(
+                BaseAlphabet::Base32.decode(base32).unwrap(),
+                data.as_bytes()
+            );
+        }
+    }
+
+    #[test]
+    fn lenient_decoding() {
+        assert_eq!(
+            BaseAlphabet::Base64Url.decode("-_8").unwrap(),
+            vec![0xFB, 0xFF]
+        );
+        assert_eq!(
+            BaseAlphabet::Base64.decode("Zm9v\n YmFy").unwrap(),
+            b"foobar"
+        );
+        assert_eq!(BaseAlphabet::Base32.decode("mzxw6").unwrap(), b"foo");
+
+        assert!(BaseAlphabet::Base64.decode("Zm9vY").is_err());
+        assert!(BaseAlphabet::Base64.decode("Zm=9v").is_err());
+        assert!(BaseAlphabet::Base64.decode("Zm9*").is_err());
+    }
+
+    #[test]
+    fn hex_dump() {
+        assert_eq!(
+            strip_hex_separators("0xDE, 0xAD\n be:ef-01_02"),
+            "DEADbeef0102"
+        );
+    }
+
+    #[test]
+    fn pem() {
+        let pem = pem_encode(&[0xAB; 50], "TEST");
+        assert_eq!(pem.lines().count(), 4);
+
+        let text = format!(
+            "junk\n-----BEGIN OTHER-----\nAA==\n-----END OTHER-----\n{}",
+            pem
+        );
+        let body = pem_body(&text, Some("TEST")).unwrap();
+        assert_eq!(BaseAlphabet::Base64.decode(&body).unwrap(), vec![0xAB; 50]);
+        assert_eq!(pem_body(&text, None).unwrap(), "AA==");
+        assert!(pem_body(&text, Some("KEY")).is_err());
+    }
+}
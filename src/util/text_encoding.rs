@@ -0,0 +1,185 @@
+use std::fmt::{Display, Formatter};
+
+/// Character encoding of string values, written in sources by its name like `utf16le`
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum TextEncoding {
+    Utf16Le,
+    Utf16Be,
+    Utf32Le,
+    Utf32Be,
+    /// ISO-8859-1, characters up to U+00FF
+    Latin1,
+    /// Characters up to U+007F
+    Ascii,
+    /// Java Modified UTF-8 of class files and `DataOutput.writeUTF`
+    ModifiedUtf8,
+}
+
+/// Character that has no representation in the target encoding
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) struct UnencodableCharacter {
+    pub(crate) character: char,
+    /// Index of the character, counted in characters
+    pub(crate) index: usize,
+    pub(crate) encoding: TextEncoding,
+}
+
+impl TextEncoding {
+    pub(crate) const ALL: [TextEncoding; 7] = [
+        TextEncoding::Utf16Le,
+        TextEncoding::Utf16Be,
+        TextEncoding::Utf32Le,
+        TextEncoding::Utf32Be,
+        TextEncoding::Latin1,
+        TextEncoding::Ascii,
+        TextEncoding::ModifiedUtf8,
+    ];
+
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            TextEncoding::Utf16Le => "utf16le",
+            TextEncoding::Utf16Be => "utf16be",
+            TextEncoding::Utf32Le => "utf32le",
+            TextEncoding::Utf32Be => "utf32be",
+            TextEncoding::Latin1 => "latin1",
+            TextEncoding::Ascii => "ascii",
+            TextEncoding::ModifiedUtf8 => "mutf8",
+        }
+    }
+
+    /// Tells whether the encoding has a byte order mark
+    pub(crate) fn has_bom(&self) -> bool {
+        matches!(
+            self,
+            TextEncoding::Utf16Le
+                | TextEncoding::Utf16Be
+                | TextEncoding::Utf32Le
+                | TextEncoding::Utf32Be
+        )
+    }
+
+    pub(crate) fn encode(&self, text: &str, bom: bool) -> Result<Vec<u8>, UnencodableCharacter> {
+        let mut bytes = Vec::new();
+        if bom {
+            bytes.extend(self.encode_char('\u{FEFF}'));
+        }
+
+        for (index, character) in text.chars().enumerate() {
+            let limit = match self {
+                TextEncoding::Latin1 => 0xFF,
+                TextEncoding::Ascii => 0x7F,
+                _ => u32::MAX,
+            };
+            if character as u32 > limit {
+                return Err(UnencodableCharacter {
+                    character,
+                    index,
+                    encoding: *self,
+                });
+            }
+
+            bytes.extend(self.encode_char(character));
+        }
+
+        Ok(bytes)
+    }
+
+    fn encode_char(&self, character: char) -> Vec<u8> {
+        let mut units = [0u16; 2];
+
+        match self {
+            TextEncoding::Utf16Le => character
+                .encode_utf16(&mut units)
+                .iter()
+                .flat_map(|unit| unit.to_le_bytes())
+                .collect(),
+            TextEncoding::Utf16Be => character
+                .encode_utf16(&mut units)
+                .iter()
+                .flat_map(|unit| unit.to_be_bytes())
+                .collect(),
+            TextEncoding::Utf32Le => (character as u32).to_le_bytes().to_vec(),
+            TextEncoding::Utf32Be => (character as u32).to_be_bytes().to_vec(),
+            TextEncoding::Latin1 | TextEncoding::Ascii => vec![character as u8],
+            TextEncoding::ModifiedUtf8 => character
+                .encode_utf16(&mut units)
+                .iter()
+                .flat_map(|unit| Self::modified_utf8_unit(*unit))
+                .collect(),
+        }
+    }
+
+    /// UTF-8 like encoding of a single UTF-16 code unit, NUL takes two bytes
+    fn modified_utf8_unit(unit: u16) -> Vec<u8> {
+        match unit {
+            0x0001..=0x007F => vec![unit as u8],
+            0x0000 | 0x0080..=0x07FF => vec![0xC0 | (unit >> 6) as u8, 0x80 | (unit & 0x3F) as u8],
+            _ => vec![
+                0xE0 | (unit >> 12) as u8,
+                0x80 | ((unit >> 6) & 0x3F) as u8,
+                0x80 | (unit & 0x3F) as u8,
+            ],
+        }
+    }
+}
+
+impl Display for TextEncoding {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl Display for UnencodableCharacter {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "character {:?} (U+{:04X}) at index {} can't be encoded in {}",
+            self.character, self.character as u32, self.index, self.encoding
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::TextEncoding;
+
+    #[test]
+    fn utf16_and_utf32() {
+        assert_eq!(
+            TextEncoding::Utf16Le.encode("a😀", true).unwrap(),
+            vec![0xFF, 0xFE, 0x61, 0x00, 0x3D, 0xD8, 0x00, 0xDE]
+        );
+        assert_eq!(
+            TextEncoding::Utf16Be.encode("a", false).unwrap(),
+            vec![0x00, 0x61]
+        );
+        assert_eq!(
+            TextEncoding::Utf32Be.encode("a", true).unwrap(),
+            vec![0x00, 0x00, 0xFE, 0xFF, 0x00, 0x00, 0x00, 0x61]
+        );
+    }
+
+    #[test]
+    fn single_byte() {
+        assert_eq!(TextEncoding::Latin1.encode("é", false).unwrap(), vec![0xE9]);
+
+        let error = TextEncoding::Ascii.encode("café", false).unwrap_err();
+        assert_eq!(error.index, 3);
+        assert_eq!(
+            error.to_string(),
+            "character 'é' (U+00E9) at index 3 can't be encoded in ascii"
+        );
+    }
+
+    #[test]
+    fn modified_utf8() {
+        assert_eq!(
+            TextEncoding::ModifiedUtf8.encode("a\0é", false).unwrap(),
+            vec![0x61, 0xC0, 0x80, 0xC3, 0xA9]
+        );
+        assert_eq!(
+            TextEncoding::ModifiedUtf8.encode("😀", false).unwrap(),
+            vec![0xED, 0xA0, 0xBD, 0xED, 0xB8, 0x80]
+        );
+    }
+}
@@ -0,0 +1,194 @@
+use std::fmt::{Display, Formatter};
+
+use num_bigint::{BigInt, Sign};
+
+/// Variable length integer encoding, written in sources by its name like `uleb128`
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum VarIntEncoding {
+    /// Unsigned LEB128 of WebAssembly and DWARF, same bytes as protobuf varint
+    UnsignedLeb128,
+    SignedLeb128,
+    /// Protobuf varint
+    Varint,
+    /// Protobuf varint of zigzag mapped signed integer, as used by `sint32` and `sint64`
+    VarintZigzag,
+    /// Big endian variable length quantity of MIDI files
+    Vlq,
+}
+
+impl VarIntEncoding {
+    pub(crate) const ALL: [VarIntEncoding; 5] = [
+        VarIntEncoding::UnsignedLeb128,
+        VarIntEncoding::SignedLeb128,
+        VarIntEncoding::Varint,
+        VarIntEncoding::VarintZigzag,
+        VarIntEncoding::Vlq,
+    ];
+
+    pub(crate) fn parse(name: &str) -> Option<VarIntEncoding> {
+        Self::ALL
+            .into_iter()
+            .find(|encoding| encoding.name() == name)
+    }
+
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            VarIntEncoding::UnsignedLeb128 => "uleb128",
+            VarIntEncoding::SignedLeb128 => "sleb128",
+            VarIntEncoding::Varint => "varint",
+            VarIntEncoding::VarintZigzag => "varint_zigzag",
+            VarIntEncoding::Vlq => "vlq",
+        }
+    }
+
+    /// Tells whether negative values can be encoded
+    pub(crate) fn is_signed(&self) -> bool {
+        matches!(
+            self,
+            VarIntEncoding::SignedLeb128 | VarIntEncoding::VarintZigzag
+        )
+    }
+
+    /// Encodes [value], returns [None] for negative values in unsigned encodings
+    pub(crate) fn encode(&self, value: &BigInt) -> Option<Vec<u8>> {
+        if value.sign() == Sign::Minus && !self.is_signed() {
+            return None;
+        }
+
+        let bytes = match self {
+            VarIntEncoding::UnsignedLeb128 | VarIntEncoding::Varint => unsigned_leb128(value),
+            VarIntEncoding::SignedLeb128 => signed_leb128(value),
+            VarIntEncoding::VarintZigzag => {
+                let zigzag = if value.sign() == Sign::Minus {
+                    (-value << 1) - 1
+                } else {
+                    value << 1
+                };
+                unsigned_leb128(&zigzag)
+            }
+            VarIntEncoding::Vlq => {
+                let mut groups = unsigned_leb128(value);
+                groups.reverse();
+                for group in groups.iter_mut() {
+                    *group |= 0x80;
+                }
+                if let Some(last) = groups.last_mut() {
+                    *last &= 0x7f;
+                }
+                groups
+            }
+        };
+
+        Some(bytes)
+    }
+}
+
+impl Display for VarIntEncoding {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+fn low_seven_bits(value: &BigInt) -> u8 {
+    // bitwise operations on negative values follow two's complement
+    let bits: BigInt = value & BigInt::from(0x7f);
+    bits.to_u32_digits().1.first().copied().unwrap_or(0) as u8
+}
+
+fn unsigned_leb128(value: &BigInt) -> Vec<u8> {
+    let mut value = value.clone();
+    let mut bytes = Vec::new();
+
+    loop {
+        let byte = low_seven_bits(&value);
+        value >>= 7;
+
+        if value.sign() == Sign::NoSign {
+            bytes.push(byte);
+            return bytes;
+        }
+        bytes.push(byte | 0x80);
+    }
+}
+
+fn signed_leb128(value: &BigInt) -> Vec<u8> {
+    let mut value = value.clone();
+    let mut bytes = Vec::new();
+
+    loop {
+        let byte = low_seven_bits(&value);
+        // arithmetic shift, negative values end up as -1
+        value >>= 7;
+
+        let sign_bit = byte & 0x40 != 0;
+        let done =
+            (value.sign() == Sign::NoSign && !sign_bit) || (value == BigInt::from(-1) && sign_bit);
+        if done {
+            bytes.push(byte);
+            return bytes;
+        }
+        bytes.push(byte | 0x80);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use num_bigint::BigInt;
+
+    use super::VarIntEncoding;
+
+    fn encode(encoding: VarIntEncoding, value: i128) -> Vec<u8> {
+        encoding.encode(&BigInt::from(value)).unwrap()
+    }
+
+    #[test]
+    fn leb128() {
+        assert_eq!(encode(VarIntEncoding::UnsignedLeb128, 0), vec![0x00]);
+        assert_eq!(
+            encode(VarIntEncoding::UnsignedLeb128, 624485),
+            vec![0xE5, 0x8E, 0x26]
+        );
+        assert_eq!(
+            encode(VarIntEncoding::SignedLeb128, -123456),
+            vec![0xC0, 0xBB, 0x78]
+        );
+        assert_eq!(encode(VarIntEncoding::SignedLeb128, 63), vec![0x3F]);
+        assert_eq!(encode(VarIntEncoding::SignedLeb128, 64), vec![0xC0, 0x00]);
+        assert_eq!(encode(VarIntEncoding::SignedLeb128, -64), vec![0x40]);
+        assert_eq!(
+            VarIntEncoding::UnsignedLeb128.encode(&BigInt::from(-1)),
+            None
+        );
+    }
+
+    #[test]
+    fn varint() {
+        assert_eq!(encode(VarIntEncoding::Varint, 150), vec![0x96, 0x01]);
+        assert_eq!(encode(VarIntEncoding::VarintZigzag, 0), vec![0x00]);
+        assert_eq!(encode(VarIntEncoding::VarintZigzag, -1), vec![0x01]);
+        assert_eq!(encode(VarIntEncoding::VarintZigzag, 1), vec![0x02]);
+        assert_eq!(
+            encode(VarIntEncoding::VarintZigzag, -2147483648),
+            vec![0xFF, 0xFF, 0xFF, 0xFF, 0x0F]
+        );
+    }
+
+    #[test]
+    fn vlq() {
+        assert_eq!(encode(VarIntEncoding::Vlq, 0x7F), vec![0x7F]);
+        assert_eq!(encode(VarIntEncoding::Vlq, 0x80), vec![0x81, 0x00]);
+        assert_eq!(
+            encode(VarIntEncoding::Vlq, 0x0FFFFFFF),
+            vec![0xFF, 0xFF, 0xFF, 0x7F]
+        );
+    }
+
+    #[test]
+    fn arbitrary_size() {
+        let value = BigInt::from(1) << 70;
+        assert_eq!(
+            VarIntEncoding::UnsignedLeb128.encode(&value).unwrap(),
+            vec![0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x01]
+        );
+    }
+}